@@ -0,0 +1,204 @@
+//! WebVTT and SRT caption output.
+//!
+//! These renderers work from the cue model rather than the consolidated segments, so
+//! the output keeps one caption per cue and can go straight back to a video player.
+//! Cue text is normalized by the parser (styling tags removed, whitespace collapsed),
+//! while speakers, identifiers and cue settings are written back out.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let vtt = format_webvtt(&cues, &metadata);
+//! assert!(vtt.starts_with("WEBVTT"));
+//!
+//! let srt = format_srt(&cues);
+//! assert!(srt.starts_with("1\n00:00:01,000 --> "));
+//! ```
+
+use crate::parser::{Cue, DocumentMetadata, unescape_markdown};
+
+/// Format cues as a normalized WebVTT file.
+///
+/// Speakers are written as `<v>` voice tags, and cue identifiers and settings are
+/// preserved. Cues without a start time are skipped.
+///
+/// # Arguments
+///
+/// * `cues` - The cues to write
+/// * `metadata` - Document metadata; the title, if any, goes in the `WEBVTT` header
+///
+/// # Returns
+///
+/// A String containing the WebVTT document.
+pub fn format_webvtt(cues: &[Cue], metadata: &DocumentMetadata) -> String {
+    let mut result = String::from("WEBVTT");
+    if let Some(ref title) = metadata.title {
+        result.push_str(" - ");
+        result.push_str(title);
+    }
+    result.push('\n');
+
+    for cue in cues {
+        let Some((start, end)) = cue_times(cue) else {
+            continue;
+        };
+
+        result.push('\n');
+        if let Some(ref id) = cue.id {
+            result.push_str(id);
+            result.push('\n');
+        }
+        result.push_str(&format!("{} --> {}", start, end));
+        if let Some(ref settings) = cue.settings {
+            result.push(' ');
+            result.push_str(settings);
+        }
+        result.push('\n');
+
+        let text = escape_cue_text(&cue.text);
+        match cue.speaker {
+            Some(ref speaker) => result.push_str(&format!(
+                "<v {}>{}</v>\n",
+                escape_cue_text(&unescape_markdown(speaker)),
+                text
+            )),
+            None => {
+                result.push_str(&text);
+                result.push('\n');
+            }
+        }
+    }
+
+    result
+}
+
+/// Format cues as a SubRip (SRT) file.
+///
+/// Cues are renumbered from 1. SRT has no voice tags, so speakers are written as a
+/// `Speaker: ` prefix on the caption text. Cues without a start time are skipped.
+///
+/// # Arguments
+///
+/// * `cues` - The cues to write
+///
+/// # Returns
+///
+/// A String containing the SRT document.
+pub fn format_srt(cues: &[Cue]) -> String {
+    let mut result = String::new();
+    let mut number = 0;
+
+    for cue in cues {
+        let Some((start, end)) = cue_times(cue) else {
+            continue;
+        };
+
+        number += 1;
+        if number > 1 {
+            result.push('\n');
+        }
+        result.push_str(&format!(
+            "{}\n{} --> {}\n",
+            number,
+            srt_timestamp(start),
+            srt_timestamp(end)
+        ));
+        if let Some(ref speaker) = cue.speaker {
+            result.push_str(&unescape_markdown(speaker));
+            result.push_str(": ");
+        }
+        result.push_str(&cue.text);
+        result.push('\n');
+    }
+
+    result
+}
+
+/// Start and end time of a cue; a missing end time falls back to the start.
+fn cue_times(cue: &Cue) -> Option<(&str, &str)> {
+    let start = cue.timestamp.as_deref()?;
+    let end = cue.end_timestamp.as_deref().unwrap_or(start);
+    Some((start, end))
+}
+
+/// Convert a WebVTT timestamp (`HH:MM:SS.mmm`) to SRT form (`HH:MM:SS,mmm`).
+fn srt_timestamp(timestamp: &str) -> String {
+    timestamp.replace('.', ",")
+}
+
+/// Escape the characters that WebVTT cue text reserves for tags and entities.
+fn escape_cue_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::VttDocument;
+
+    fn cue(start: &str, end: &str, speaker: Option<&str>, text: &str) -> Cue {
+        Cue {
+            timestamp: Some(start.to_string()),
+            end_timestamp: Some(end.to_string()),
+            speaker: speaker.map(str::to_string),
+            text: text.to_string(),
+            id: None,
+            settings: None,
+        }
+    }
+
+    #[test]
+    fn test_format_webvtt() {
+        let mut first = cue(
+            "00:00:01.000",
+            "00:00:02.000",
+            Some("John\\*Doe"),
+            "Tom & Jerry",
+        );
+        first.id = Some("1".to_string());
+        first.settings = Some("align:start".to_string());
+        let cues = vec![first, cue("00:00:03.000", "00:00:04.000", None, "[Music]")];
+        let metadata = DocumentMetadata {
+            title: Some("Standup".to_string()),
+            ..DocumentMetadata::default()
+        };
+
+        assert_eq!(
+            format_webvtt(&cues, &metadata),
+            "WEBVTT - Standup\n\n\
+             1\n00:00:01.000 --> 00:00:02.000 align:start\n<v John*Doe>Tom &amp; Jerry</v>\n\n\
+             00:00:03.000 --> 00:00:04.000\n[Music]\n"
+        );
+    }
+
+    #[test]
+    fn test_format_webvtt_round_trip() {
+        let content = "WEBVTT\n\nq1\n00:00:01.000 --> 00:00:02.500 line:10%\n<v Alice>1 < 2 &amp; 3</v>\n\n\
+                       00:00:03.000 --> 00:00:04.000\n<v Bob>Sure.</v>\n";
+        let doc = VttDocument::parse_str(content).unwrap();
+
+        let rendered = format_webvtt(&doc.cues, &doc.metadata);
+        let reparsed = VttDocument::parse_str(&rendered).unwrap();
+        assert_eq!(reparsed.cues, doc.cues);
+    }
+
+    #[test]
+    fn test_format_srt() {
+        let cues = vec![
+            cue("00:00:01.000", "00:00:02.000", Some("Alice"), "Hello."),
+            Cue {
+                timestamp: None,
+                ..cue("00:00:02.000", "00:00:03.000", None, "No timing")
+            },
+            cue("01:02:03.456", "01:02:04.000", None, "[Music]"),
+        ];
+
+        assert_eq!(
+            format_srt(&cues),
+            "1\n00:00:01,000 --> 00:00:02,000\nAlice: Hello.\n\n\
+             2\n01:02:03,456 --> 01:02:04,000\n[Music]\n"
+        );
+    }
+}
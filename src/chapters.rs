@@ -0,0 +1,294 @@
+//! Chapters from a WebVTT chapters track.
+//!
+//! Video platforms often export a `kind=chapters` track next to the captions: a WebVTT
+//! file whose cues are chapter titles. `--chapters chapters.vtt` reads it, splits
+//! speaker turns that cross a chapter boundary, and Markdown output gets a `##`
+//! heading at the start of each chapter (and, with `--toc`, a linked table of
+//! contents). Other formats keep the split turns, and structured formats list the
+//! chapters in their metadata.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let chapters = read_chapters(Path::new("chapters.vtt"))?;
+//! let segments = consolidate_by_chapter(&cues, &chapters, "Unknown", TimestampMode::None, None);
+//! ```
+
+use crate::cli::TimestampMode;
+use crate::consolidator::{SpeakerSegment, consolidate_cues, slug};
+use crate::error::VttError;
+use crate::parser::{Cue, VttDocument};
+use crate::timestamp::parse_timestamp;
+use serde::Serialize;
+use std::path::Path;
+
+/// A chapter of the recording.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Chapter {
+    /// Chapter title
+    pub title: String,
+    /// Start time of the chapter
+    pub start: String,
+    /// End time of the chapter, if known
+    pub end: Option<String>,
+    /// Anchor id of the chapter heading, such as `chapter-introduction`
+    pub id: String,
+}
+
+impl Chapter {
+    /// Start of the chapter in milliseconds.
+    fn start_millis(&self) -> u64 {
+        parse_timestamp(&self.start).unwrap_or_default()
+    }
+}
+
+/// Read the chapters from a WebVTT chapters file, in order of start time.
+///
+/// # Arguments
+///
+/// * `path` - Path to the chapters `.vtt` file
+///
+/// # Returns
+///
+/// The chapters, each with a unique anchor id.
+///
+/// # Errors
+///
+/// Returns the errors of [`VttDocument::parse`], and `VttError::ParseError` if the
+/// file has no chapter cues.
+pub fn read_chapters(path: &Path) -> Result<Vec<Chapter>, VttError> {
+    let document = VttDocument::parse(path)?;
+
+    let mut cues: Vec<(u64, Cue)> = document
+        .cues
+        .into_iter()
+        .filter(|cue| !cue.text.trim().is_empty())
+        .filter_map(|cue| Some((parse_timestamp(cue.timestamp.as_deref()?)?, cue)))
+        .collect();
+    cues.sort_by_key(|(start, _)| *start);
+
+    let mut chapters: Vec<Chapter> = Vec::with_capacity(cues.len());
+    for (_, cue) in cues {
+        let title = cue.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let id = unique_id(&format!("chapter-{}", slug(&title)), &chapters);
+        chapters.push(Chapter {
+            title,
+            start: cue.timestamp.unwrap_or_default(),
+            end: cue.end_timestamp,
+            id,
+        });
+    }
+
+    if chapters.is_empty() {
+        return Err(VttError::ParseError {
+            reason: format!("no chapters found in {}", path.display()),
+        });
+    }
+
+    Ok(chapters)
+}
+
+/// An anchor id not yet used by any of the chapters, adding `-2`, `-3`, ... to `base`
+/// when needed.
+pub fn unique_id(base: &str, chapters: &[Chapter]) -> String {
+    let mut id = base.to_string();
+    let mut repeat = 1;
+    while chapters.iter().any(|chapter| chapter.id == id) {
+        repeat += 1;
+        id = format!("{}-{}", base, repeat);
+    }
+    id
+}
+
+/// Consolidate cues into speaker segments without letting a turn cross a chapter
+/// boundary.
+///
+/// A cue belongs to the chapter it starts in; consecutive cues of the same speaker in
+/// different chapters become separate turns.
+///
+/// # Arguments
+///
+/// * `cues` - The parsed cues, in order
+/// * `chapters` - The chapters, in order of start time
+/// * `unknown_speaker_label` - The label to use for cues without speaker attribution
+/// * `timestamp_mode` - How to include timestamps in the output (None, First, or Each)
+/// * `max_gap` - Longest silence, in milliseconds, that a segment may span
+///
+/// # Returns
+///
+/// The speaker segments of every chapter, in order.
+pub fn consolidate_by_chapter(
+    cues: &[Cue],
+    chapters: &[Chapter],
+    unknown_speaker_label: &str,
+    timestamp_mode: TimestampMode,
+    max_gap: Option<u64>,
+) -> Vec<SpeakerSegment> {
+    let chapter_of = |cue: &Cue| {
+        let start = cue.timestamp.as_deref().and_then(parse_timestamp);
+        start.map(|start| {
+            chapters
+                .iter()
+                .take_while(|chapter| chapter.start_millis() <= start)
+                .count()
+        })
+    };
+
+    let mut segments = Vec::new();
+    let mut group_start = 0;
+    let mut group_chapter = cues.first().and_then(chapter_of);
+
+    for (index, cue) in cues.iter().enumerate().skip(1) {
+        // Cues without a start time stay with the chapter before them
+        let chapter = chapter_of(cue).or(group_chapter);
+        if chapter != group_chapter {
+            segments.extend(consolidate_cues(
+                &cues[group_start..index],
+                unknown_speaker_label,
+                timestamp_mode,
+                max_gap,
+            ));
+            group_start = index;
+            group_chapter = chapter;
+        }
+    }
+    segments.extend(consolidate_cues(
+        &cues[group_start..],
+        unknown_speaker_label,
+        timestamp_mode,
+        max_gap,
+    ));
+
+    segments
+}
+
+/// The chapters whose heading goes before each segment.
+///
+/// A chapter's heading goes before the first segment starting at or after the
+/// chapter's start; chapters after the last segment are returned in the final entry,
+/// so the result has one more entry than there are segments.
+///
+/// # Arguments
+///
+/// * `chapters` - The chapters, in order of start time
+/// * `segments` - The speaker segments
+pub fn chapter_breaks<'a>(
+    chapters: &'a [Chapter],
+    segments: &[SpeakerSegment],
+) -> Vec<Vec<&'a Chapter>> {
+    let mut breaks: Vec<Vec<&Chapter>> = vec![Vec::new(); segments.len() + 1];
+    let mut remaining = chapters.iter().peekable();
+
+    for (index, segment) in segments.iter().enumerate() {
+        let Some(start) = segment
+            .timestamps
+            .first()
+            .and_then(|timestamp| parse_timestamp(timestamp))
+        else {
+            continue;
+        };
+        while let Some(chapter) = remaining.next_if(|chapter| chapter.start_millis() <= start) {
+            breaks[index].push(chapter);
+        }
+    }
+    breaks[segments.len()].extend(remaining);
+
+    breaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn cue(speaker: &str, start: &str, text: &str) -> Cue {
+        Cue {
+            timestamp: Some(start.to_string()),
+            end_timestamp: None,
+            speaker: Some(speaker.to_string()),
+            text: text.to_string(),
+            id: None,
+            settings: None,
+        }
+    }
+
+    fn chapter(title: &str, start: &str) -> Chapter {
+        Chapter {
+            title: title.to_string(),
+            start: start.to_string(),
+            end: None,
+            id: format!("chapter-{}", slug(title)),
+        }
+    }
+
+    #[test]
+    fn test_read_chapters() {
+        let path = std::env::temp_dir().join("test_read_chapters.vtt");
+        fs::write(
+            &path,
+            "WEBVTT\n\n00:05:00.000 --> 00:10:00.000\nQ&A\n\n\
+             00:00:00.000 --> 00:05:00.000\nIntro\n\n\
+             00:10:00.000 --> 00:12:00.000\nQ&A\n",
+        )
+        .unwrap();
+        let chapters = read_chapters(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        let ids: Vec<&str> = chapters.iter().map(|chapter| chapter.id.as_str()).collect();
+        assert_eq!(ids, vec!["chapter-intro", "chapter-q-a", "chapter-q-a-2"]);
+        assert_eq!(chapters[0].start, "00:00:00.000");
+        assert_eq!(chapters[0].end.as_deref(), Some("00:05:00.000"));
+    }
+
+    #[test]
+    fn test_consolidate_by_chapter_splits_turns() {
+        let cues = vec![
+            cue("Alice", "00:04:50.000", "Before the break."),
+            cue("Alice", "00:05:02.000", "After it."),
+            cue("Bob", "00:05:10.000", "Hi."),
+        ];
+        let chapters = vec![
+            chapter("Intro", "00:00:00.000"),
+            chapter("Demo", "00:05:00.000"),
+        ];
+
+        let segments =
+            consolidate_by_chapter(&cues, &chapters, "Unknown", TimestampMode::None, None);
+        let texts: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect();
+        assert_eq!(texts, vec!["Before the break.", "After it.", "Hi."]);
+
+        let breaks = chapter_breaks(&chapters, &segments);
+        let titles: Vec<Vec<&str>> = breaks
+            .iter()
+            .map(|chapters| {
+                chapters
+                    .iter()
+                    .map(|chapter| chapter.title.as_str())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(titles, vec![vec!["Intro"], vec!["Demo"], vec![], vec![]]);
+    }
+
+    #[test]
+    fn test_chapter_breaks_empty_and_trailing_chapters() {
+        let segments = consolidate_cues(
+            &[cue("Alice", "00:07:00.000", "Hello.")],
+            "Unknown",
+            TimestampMode::None,
+            None,
+        );
+        let chapters = vec![
+            chapter("Intro", "00:00:00.000"),
+            chapter("Demo", "00:05:00.000"),
+            chapter("Wrap-up", "00:30:00.000"),
+        ];
+
+        let breaks = chapter_breaks(&chapters, &segments);
+        assert_eq!(breaks[0].len(), 2);
+        assert_eq!(breaks[1][0].title, "Wrap-up");
+    }
+}
@@ -4,7 +4,10 @@
 //! validates argument combinations, and provides helpful error messages and usage text.
 
 use crate::error::VttError;
+use crate::output;
+use crate::timestamp::{ClockTime, parse_duration, parse_offset, parse_utc_offset};
 use clap::{Parser, ValueEnum};
+use serde::Serialize;
 use std::path::{Path, PathBuf};
 
 /// VTT to Markdown converter - Convert WebVTT transcript files to readable Markdown
@@ -18,12 +21,15 @@ use std::path::{Path, PathBuf};
                   speaker names and consolidated text paragraphs."
 )]
 pub struct Args {
-    /// Path to the input VTT file
-    #[arg(value_name = "INPUT", help = "Path to the input VTT file")]
+    /// Path to the input VTT file, HLS (.m3u8) playlist, or MP4/Matroska media file
+    #[arg(
+        value_name = "INPUT",
+        help = "Path to the input VTT file, HLS (.m3u8) playlist, or MP4/Matroska media file"
+    )]
     pub input: PathBuf,
 
-    /// Path to the output Markdown file (defaults to INPUT with .md extension)
-    #[arg(value_name = "OUTPUT", help = "Path to the output Markdown file")]
+    /// Path to the output file (defaults to INPUT with the output format's extension)
+    #[arg(value_name = "OUTPUT", help = "Path to the output file")]
     pub output: Option<PathBuf>,
 
     /// Overwrite existing output file
@@ -44,8 +50,8 @@ pub struct Args {
     )]
     pub no_clobber: bool,
 
-    /// Print Markdown to stdout instead of writing to file
-    #[arg(long, help = "Print Markdown to stdout instead of writing to file")]
+    /// Print output to stdout instead of writing to file
+    #[arg(long, help = "Print output to stdout instead of writing to file")]
     pub stdout: bool,
 
     /// Custom label for cues without speaker attribution
@@ -73,6 +79,50 @@ pub struct Args {
     )]
     pub no_filter_unknown: bool,
 
+    /// Longest silence within a speaker turn
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = parse_positive_duration,
+        help = "Start a new speaker turn when the silence between cues is longer than DURATION (e.g. 90, 90s, 3m, 1m30s or 00:01:30)"
+    )]
+    pub max_gap: Option<u64>,
+
+    /// Mark long silences between turns
+    #[arg(
+        long,
+        requires = "max_gap",
+        help = "Put a [pause 3m12s] marker before speaker turns that follow a silence longer than --max-gap in Markdown output"
+    )]
+    pub pause_markers: bool,
+
+    /// Sentences per paragraph within a speaker turn
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        help = "Start a new paragraph within a speaker turn after N sentences"
+    )]
+    pub paragraph_sentences: Option<usize>,
+
+    /// Words per paragraph within a speaker turn
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        help = "Start a new paragraph within a speaker turn at the first sentence end after N words"
+    )]
+    pub paragraph_words: Option<usize>,
+
+    /// Longest pause within a paragraph
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = parse_positive_duration,
+        help = "Start a new paragraph within a speaker turn after a pause longer than DURATION (e.g. 5s)"
+    )]
+    pub paragraph_pause: Option<u64>,
+
     /// Disable auto-increment of output filename on collision
     #[arg(
         long,
@@ -85,9 +135,305 @@ pub struct Args {
         long,
         value_name = "MODE",
         default_value = "none",
-        help = "Timestamp inclusion mode: none, first (first cue of each speaker turn), or each (every cue)"
+        help = "Timestamp inclusion mode: none, first (first cue of each speaker turn), or each (every cue, inline within the turn)"
     )]
     pub include_timestamps: TimestampMode,
+
+    /// Precision of displayed timestamps
+    #[arg(
+        long,
+        value_name = "PRECISION",
+        help = "Show timestamps as hh:mm:ss.mmm, hh:mm:ss, or mm:ss (minutes keep counting past the hour) (default: as in the input; hh:mm:ss for wall-clock times)"
+    )]
+    pub timestamp_precision: Option<TimestampPrecision>,
+
+    /// Constant shift applied to displayed timestamps
+    #[arg(
+        long,
+        value_name = "OFFSET",
+        allow_hyphen_values = true,
+        value_parser = parse_timestamp_offset,
+        help = "Add [-]HH:MM:SS[.mmm] to every displayed timestamp, e.g. when the recording started late"
+    )]
+    pub timestamp_offset: Option<i64>,
+
+    /// Wall-clock time at the start of the recording
+    #[arg(
+        long,
+        value_name = "TIME",
+        value_parser = parse_start_time,
+        help = "Show timestamps as wall-clock times from this start time: HH:MM[:SS], optionally with Z or a +HH:MM UTC offset (overrides the --ics start)"
+    )]
+    pub start_time: Option<ClockTime>,
+
+    /// Time zone for wall-clock timestamps
+    #[arg(
+        long,
+        value_name = "ZONE",
+        allow_hyphen_values = true,
+        value_parser = parse_time_zone,
+        help = "Show wall-clock timestamps in this UTC offset (Z or +HH:MM); the start time must have a UTC offset"
+    )]
+    pub time_zone: Option<i32>,
+
+    /// Show turn timestamps relative to the previous turn
+    #[arg(
+        long,
+        help = "Show each speaker turn's timestamp after the first as +MM:SS since the previous turn"
+    )]
+    pub relative_timestamps: bool,
+
+    /// Subtitle track to use from a media file
+    #[arg(
+        long,
+        value_name = "TRACK",
+        help = "Subtitle track to extract from an MP4/Matroska input: 1-based position, track ID, language code, or track name (default: first WebVTT track)"
+    )]
+    pub track: Option<String>,
+
+    /// Input format, overriding detection from file contents and extension
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        value_parser = clap::builder::PossibleValuesParser::new(crate::input::format_names()),
+        help = "Input format, overriding detection from file contents and extension"
+    )]
+    pub from: Option<String>,
+
+    /// Output format
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "markdown",
+        value_parser = clap::builder::PossibleValuesParser::new(crate::output::format_names()),
+        help = "Output format; also sets the extension of the derived output path"
+    )]
+    pub to: String,
+
+    /// Audio or video file to embed as a player in HTML output
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Audio or video file to embed in HTML output; clicking a speaker turn seeks the player (requires --to html)"
+    )]
+    pub media: Option<String>,
+
+    /// What JSON, CSV or TSV output contains
+    #[arg(
+        long,
+        value_enum,
+        value_name = "RECORDS",
+        help = "Records to emit with --to json, jsonl, csv or tsv: segments (consolidated speaker turns, default) or cues (individual captions)"
+    )]
+    pub records: Option<RecordKind>,
+
+    /// Columns for CSV and TSV output
+    #[arg(
+        long,
+        value_enum,
+        value_name = "COLUMNS",
+        value_delimiter = ',',
+        help = "Comma-separated columns to write with --to csv or tsv, in order (default: start,end,duration,speaker,words,text)"
+    )]
+    pub columns: Vec<Column>,
+
+    /// Layout of speaker turns in Markdown output
+    #[arg(
+        long,
+        value_enum,
+        value_name = "STYLE",
+        help = "Layout of speaker turns with --to markdown: bold (default), heading, blockquote, definition, table, or chat"
+    )]
+    pub style: Option<MarkdownStyle>,
+
+    /// Put a separator line between speaker turns in Markdown output
+    #[arg(
+        long,
+        help = "Put a horizontal rule between speaker turns in Markdown output (not used by the table style)"
+    )]
+    pub separator: bool,
+
+    /// Leave out the blank line between speaker turns in Markdown output
+    #[arg(
+        long,
+        help = "Separate speaker turns with a single line break instead of a blank line in Markdown output"
+    )]
+    pub compact: bool,
+
+    /// Give each speaker turn a stable anchor
+    #[arg(
+        long,
+        help = "Give each speaker turn in Markdown output a stable anchor from its start time and speaker, e.g. #t-00-12-34-alice"
+    )]
+    pub anchors: bool,
+
+    /// Start Markdown output with an index of speaker turns
+    #[arg(
+        long,
+        help = "Start Markdown output with an index of speaker turns linking to their anchors (implies --anchors)"
+    )]
+    pub index: bool,
+
+    /// Chapters track with section titles
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "WebVTT chapters track whose cues are chapter titles; speaker turns are split at chapter boundaries and Markdown output gets a heading per chapter"
+    )]
+    pub chapters: Option<PathBuf>,
+
+    /// Split the transcript into sections at long silences and topic shifts
+    #[arg(
+        long,
+        conflicts_with = "chapters",
+        help = "Split the transcript into sections at long silences and topic shifts, each headed by its top keywords in Markdown output"
+    )]
+    pub topics: bool,
+
+    /// Start Markdown output with a table of contents of the chapters
+    #[arg(
+        long,
+        help = "Start Markdown output with a table of contents linking to the chapter or section headings (requires --chapters or --topics)"
+    )]
+    pub toc: bool,
+
+    /// Calendar invite with the meeting's details
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Calendar invite (.ics) whose event supplies the title, start time, organizer and attendees; timestamps are shown as wall-clock times"
+    )]
+    pub ics: Option<PathBuf>,
+
+    /// Start Markdown output with a YAML front matter block
+    #[arg(
+        long,
+        help = "Start Markdown output with a YAML front matter block (title, date, duration, participants, counts, and source hash)"
+    )]
+    pub front_matter: bool,
+
+    /// Handlebars template to render instead of a built-in format
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["style", "separator", "compact", "anchors", "index", "toc", "pause_markers", "front_matter", "media", "media_url", "records", "columns", "interviewer"],
+        help = "Render with a Handlebars template file instead of a built-in format (--to then only sets the output extension)"
+    )]
+    pub template: Option<PathBuf>,
+
+    /// Recording URL that timestamps link into
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Link timestamps in Markdown output to this recording, at the moment they mark"
+    )]
+    pub media_url: Option<String>,
+
+    /// How timestamp links point into the recording
+    #[arg(
+        long,
+        value_name = "STYLE",
+        requires = "media_url",
+        help = "How timestamp links give the start time: youtube, vimeo, stream, query (?t=SECONDS), or fragment (#t=HH:MM:SS) (default: detected from the --media-url host, else fragment)"
+    )]
+    pub link_style: Option<LinkStyle>,
+
+    /// Interviewer speaker names for the Q&A layout
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Speaker whose turns are questions with --to qa; repeat for several interviewers (default: the first speaker)"
+    )]
+    pub interviewer: Vec<String>,
+}
+
+/// Which records structured output formats emit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordKind {
+    /// Consolidated speaker turns
+    #[default]
+    Segments,
+    /// Individual caption cues
+    Cues,
+}
+
+/// Layout of speaker turns in Markdown output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum MarkdownStyle {
+    /// `**Speaker:** text` paragraphs
+    #[default]
+    Bold,
+    /// A `###` heading with the speaker name above the text
+    Heading,
+    /// `> **Speaker:** text` blockquotes
+    Blockquote,
+    /// A definition list with the speaker as the term
+    Definition,
+    /// A table with one row per turn
+    Table,
+    /// A chat log with one bullet per turn
+    Chat,
+}
+
+/// A column of CSV and TSV output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Column {
+    /// Start time of the record
+    Start,
+    /// End time of the record
+    End,
+    /// Seconds between start and end
+    Duration,
+    /// Speaker name
+    Speaker,
+    /// Number of words in the text
+    Words,
+    /// The spoken text
+    Text,
+}
+
+impl Column {
+    /// Name of the column in the header row
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Start => "start",
+            Column::End => "end",
+            Column::Duration => "duration",
+            Column::Speaker => "speaker",
+            Column::Words => "words",
+            Column::Text => "text",
+        }
+    }
+}
+
+/// How timestamp links give the start time to the recording's player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LinkStyle {
+    /// YouTube: `?t=SECONDS`
+    Youtube,
+    /// Vimeo: `#t=SECONDSs`
+    Vimeo,
+    /// Microsoft Stream: `?st=SECONDS`
+    Stream,
+    /// A `?t=SECONDS` query parameter
+    Query,
+    /// A `#t=HH:MM:SS` media fragment
+    Fragment,
+}
+
+/// Precision of displayed timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TimestampPrecision {
+    /// `HH:MM:SS.mmm`
+    #[value(name = "hh:mm:ss.mmm")]
+    Millis,
+    /// `HH:MM:SS`
+    #[value(name = "hh:mm:ss")]
+    Seconds,
+    /// `MM:SS`, with minutes counting past the hour
+    #[value(name = "mm:ss")]
+    Minutes,
 }
 
 /// Timestamp inclusion mode for output
@@ -97,7 +443,7 @@ pub enum TimestampMode {
     None,
     /// Include timestamp from first cue of each speaker turn
     First,
-    /// Include timestamp for each original cue
+    /// Include timestamp for each original cue, inline within the speaker turn
     Each,
 }
 
@@ -111,16 +457,91 @@ impl Args {
     ///
     /// Returns `VttError::UsageError` if:
     /// - Input and output paths are the same
+    /// - `--media` is used with an output format other than HTML
+    /// - `--records` is used with an output format other than JSON, JSON Lines, CSV or TSV
+    /// - `--columns` is used with an output format other than CSV or TSV
+    /// - `--interviewer` is used with an output format other than Q&A
+    /// - `--media-url` is used with an output format other than Markdown or Q&A
+    /// - `--style`, `--separator`, `--compact`, `--anchors`, `--index` or `--front-matter`
+    ///   is used with an output format other than Markdown
     /// - Other validation constraints are violated
     pub fn validate(&mut self) -> Result<(), VttError> {
+        let extension = output::find_format(&self.to)?.extension();
+
+        if self.media.is_some() && !self.to.eq_ignore_ascii_case("html") {
+            return Err(VttError::UsageError {
+                reason: "--media requires --to html".to_string(),
+            });
+        }
+
+        if self.records.is_some() && !matches!(self.to.as_str(), "json" | "jsonl" | "csv" | "tsv") {
+            return Err(VttError::UsageError {
+                reason: "--records requires --to json, jsonl, csv or tsv".to_string(),
+            });
+        }
+
+        if !self.columns.is_empty() && !matches!(self.to.as_str(), "csv" | "tsv") {
+            return Err(VttError::UsageError {
+                reason: "--columns requires --to csv or --to tsv".to_string(),
+            });
+        }
+
+        if !self.interviewer.is_empty() && self.to != "qa" {
+            return Err(VttError::UsageError {
+                reason: "--interviewer requires --to qa".to_string(),
+            });
+        }
+
+        if self.media_url.is_some() && !matches!(self.to.as_str(), "markdown" | "qa") {
+            return Err(VttError::UsageError {
+                reason: "--media-url requires --to markdown or --to qa".to_string(),
+            });
+        }
+
+        if (self.style.is_some() || self.separator || self.compact) && self.to != "markdown" {
+            return Err(VttError::UsageError {
+                reason: "--style, --separator and --compact require --to markdown".to_string(),
+            });
+        }
+
+        if (self.anchors || self.index) && self.to != "markdown" {
+            return Err(VttError::UsageError {
+                reason: "--anchors and --index require --to markdown".to_string(),
+            });
+        }
+
+        if self.toc && self.chapters.is_none() && !self.topics {
+            return Err(VttError::UsageError {
+                reason: "--toc requires --chapters or --topics".to_string(),
+            });
+        }
+
+        if self.toc && self.to != "markdown" {
+            return Err(VttError::UsageError {
+                reason: "--toc requires --to markdown".to_string(),
+            });
+        }
+
+        if self.pause_markers && self.to != "markdown" {
+            return Err(VttError::UsageError {
+                reason: "--pause-markers requires --to markdown".to_string(),
+            });
+        }
+
+        if self.front_matter && self.to != "markdown" {
+            return Err(VttError::UsageError {
+                reason: "--front-matter requires --to markdown".to_string(),
+            });
+        }
+
         // Derive output path if not specified and not using stdout
         if self.output.is_none() && !self.stdout {
             if self.no_auto_increment {
                 // Old behavior: simple extension replacement
-                self.output = Some(self.input.with_extension("md"));
+                self.output = Some(self.input.with_extension(extension));
             } else {
                 // New default: auto-increment on collision
-                self.output = Some(derive_output_path(&self.input));
+                self.output = Some(derive_output_path(&self.input, extension));
             }
         }
 
@@ -146,10 +567,35 @@ impl Args {
     }
 }
 
-/// Derive output path from input path by replacing extension with .md
-/// and finding next available filename if collision occurs.
-fn derive_output_path(input: &Path) -> PathBuf {
-    let base_output = input.with_extension("md");
+/// Parse `--timestamp-offset`.
+fn parse_timestamp_offset(value: &str) -> Result<i64, String> {
+    parse_offset(value).ok_or_else(|| "expected [-]HH:MM:SS[.mmm] or [-]MM:SS[.mmm]".to_string())
+}
+
+/// Parse `--max-gap` and `--paragraph-pause`.
+fn parse_positive_duration(value: &str) -> Result<u64, String> {
+    parse_duration(value)
+        .filter(|&millis| millis > 0)
+        .ok_or_else(|| {
+            "expected a positive duration such as 90, 90s, 3m, 1m30s or 00:01:30".to_string()
+        })
+}
+
+/// Parse `--start-time`.
+fn parse_start_time(value: &str) -> Result<ClockTime, String> {
+    ClockTime::parse(value)
+        .ok_or_else(|| "expected HH:MM or HH:MM:SS, optionally followed by Z or +HH:MM".to_string())
+}
+
+/// Parse `--time-zone`.
+fn parse_time_zone(value: &str) -> Result<i32, String> {
+    parse_utc_offset(value).ok_or_else(|| "expected Z, UTC, or a +HH:MM UTC offset".to_string())
+}
+
+/// Derive output path from input path by replacing its extension with the
+/// output format's extension and finding next available filename if collision occurs.
+fn derive_output_path(input: &Path, extension: &str) -> PathBuf {
+    let base_output = input.with_extension(extension);
     find_available_path(&base_output)
 }
 
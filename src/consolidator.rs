@@ -2,7 +2,9 @@
 //!
 //! This module implements the logic to merge consecutive cues from the same speaker
 //! into coherent paragraphs. It handles speaker changes, applies unknown speaker labels,
-//! and joins text intelligently while respecting sentence boundaries.
+//! and joins text intelligently while respecting sentence boundaries. Optionally, a
+//! long enough silence starts a new turn even when the speaker stays the same, and
+//! [`split_paragraphs`] breaks long turns into paragraphs.
 //!
 //! # Example
 //!
@@ -12,45 +14,156 @@
 //! use vtt_to_md::cli::TimestampMode;
 //!
 //! let cues = vec![
-//!     Cue { speaker: Some("Alice".to_string()), text: "Hello.".to_string(), timestamp: Some("00:00:01.000".to_string()) },
-//!     Cue { speaker: Some("Alice".to_string()), text: "How are you?".to_string(), timestamp: Some("00:00:02.000".to_string()) },
-//!     Cue { speaker: Some("Bob".to_string()), text: "I'm fine!".to_string(), timestamp: Some("00:00:03.000".to_string()) },
+//!     Cue { speaker: Some("Alice".to_string()), text: "Hello.".to_string(), timestamp: Some("00:00:01.000".to_string()), end_timestamp: None, id: None, settings: None },
+//!     Cue { speaker: Some("Alice".to_string()), text: "How are you?".to_string(), timestamp: Some("00:00:02.000".to_string()), end_timestamp: None, id: None, settings: None },
+//!     Cue { speaker: Some("Bob".to_string()), text: "I'm fine!".to_string(), timestamp: Some("00:00:03.000".to_string()), end_timestamp: None, id: None, settings: None },
 //! ];
 //!
-//! let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None);
+//! let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None, None);
 //! // Result: 2 segments - Alice's text consolidated, Bob separate
 //! assert_eq!(segments.len(), 2);
 //! assert_eq!(segments[0].text, "Hello. How are you?");
 //! ```
 
 use crate::cli::TimestampMode;
-use crate::parser::Cue;
+use crate::parser::{Cue, serialize_speaker, unescape_markdown};
+use crate::timestamp::parse_timestamp;
+use serde::Serialize;
 
 /// Represents a consolidated speaker segment with speaker name, text, and optional timestamps.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct SpeakerSegment {
     /// The speaker's name (or custom label for unknown speakers)
+    #[serde(serialize_with = "serialize_speaker")]
     pub speaker: String,
-    /// The consolidated text from all consecutive cues by this speaker
+    /// The consolidated text from all consecutive cues by this speaker, with a blank
+    /// line between paragraphs
     pub text: String,
     /// Optional timestamp for the segment (used by TimestampMode::First)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp: Option<String>,
     /// Vector of all timestamps from original cues (used by TimestampMode::Each)
-    /// When TimestampMode::Each is used, the markdown formatter uses the first timestamp
-    /// to indicate when the speaker turn began.
+    /// When TimestampMode::Each is used, formatters use the first timestamp to indicate
+    /// when the speaker turn began.
     pub timestamps: Vec<String>,
+    /// End time of the last cue in the segment, if known
+    pub end_timestamp: Option<String>,
+    /// The cues the text was consolidated from, in order, so formatters can mark where
+    /// each one began (used by TimestampMode::Each)
+    #[serde(skip)]
+    pub cues: Vec<SegmentCue>,
+}
+
+/// The text of one cue within a speaker segment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentCue {
+    /// Start time of the cue, if known
+    pub timestamp: Option<String>,
+    /// End time of the cue, if known
+    pub end_timestamp: Option<String>,
+    /// The cue's trimmed text
+    pub text: String,
+    /// Whether the cue starts a new paragraph within the turn
+    pub new_paragraph: bool,
+}
+
+/// When to start a new paragraph within a speaker turn.
+///
+/// Paragraphs only break between cues. The sentence and word limits wait for a cue
+/// that ends a sentence, unless the paragraph has grown to twice the word limit; a
+/// long enough pause breaks the paragraph right away.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ParagraphRules {
+    /// Sentences per paragraph
+    pub max_sentences: Option<usize>,
+    /// Words per paragraph
+    pub max_words: Option<usize>,
+    /// Longest pause, in milliseconds, within a paragraph
+    pub max_pause: Option<u64>,
+}
+
+impl SpeakerSegment {
+    /// The timestamp to display for this segment under the given mode.
+    ///
+    /// `First` uses the segment's timestamp; `Each` uses the first of the cue timestamps
+    /// to indicate when the speaker turn began.
+    pub fn display_timestamp(&self, timestamp_mode: TimestampMode) -> Option<&str> {
+        match timestamp_mode {
+            TimestampMode::None => None,
+            TimestampMode::First => self.timestamp.as_deref(),
+            TimestampMode::Each => self.timestamps.first().map(String::as_str),
+        }
+    }
+
+    /// The segment's paragraphs, each split into parts with the timestamp to show
+    /// before them.
+    ///
+    /// Under `Each`, every cue is a part and those after the first carry their start
+    /// time (the first cue's time is the turn's timestamp). Otherwise, or when the cue
+    /// boundaries are unknown, each paragraph is a single part, and under `First` the
+    /// paragraphs after the first carry their start time. Joining the parts of each
+    /// paragraph with spaces, and the paragraphs with blank lines, gives the segment
+    /// text.
+    pub fn paragraphs(&self, timestamp_mode: TimestampMode) -> Vec<Vec<(Option<&str>, &str)>> {
+        if timestamp_mode != TimestampMode::Each || self.cues.is_empty() {
+            let starts = self
+                .cues
+                .iter()
+                .filter(|cue| cue.new_paragraph)
+                .map(|cue| match timestamp_mode {
+                    TimestampMode::None => None,
+                    _ => cue.timestamp.as_deref(),
+                });
+            return self
+                .text
+                .split("\n\n")
+                .zip(std::iter::once(None).chain(starts))
+                .map(|(text, timestamp)| vec![(timestamp, text)])
+                .collect();
+        }
+
+        let mut paragraphs: Vec<Vec<(Option<&str>, &str)>> = Vec::new();
+        for (index, cue) in self.cues.iter().enumerate() {
+            if index == 0 || cue.new_paragraph {
+                paragraphs.push(Vec::new());
+            }
+            let timestamp = if index == 0 {
+                None
+            } else {
+                cue.timestamp.as_deref()
+            };
+            if let Some(paragraph) = paragraphs.last_mut() {
+                paragraph.push((timestamp, cue.text.as_str()));
+            }
+        }
+        paragraphs
+    }
+}
+
+/// A participant in the transcript: speaker name, number of turns, and number of words.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Participant<'a> {
+    /// The speaker's name, as it appears in the segments
+    pub speaker: &'a str,
+    /// Number of speaker turns
+    pub turns: usize,
+    /// Number of words spoken across all turns
+    pub words: usize,
 }
 
 /// Consolidate a list of parsed cues into speaker segments.
 ///
 /// This function groups consecutive cues from the same speaker into single segments,
 /// joins their text intelligently, and handles timestamp inclusion based on the mode.
+/// With `max_gap`, a cue that starts more than `max_gap` after the previous cue ended
+/// (or started, if its end is unknown) begins a new segment.
 ///
 /// # Arguments
 ///
 /// * `cues` - The list of parsed cues from a VTT document
 /// * `unknown_speaker_label` - The label to use for cues without speaker attribution
 /// * `timestamp_mode` - How to include timestamps in the output (None, First, or Each)
+/// * `max_gap` - Longest silence, in milliseconds, that a segment may span
 ///
 /// # Returns
 ///
@@ -60,23 +173,27 @@ pub struct SpeakerSegment {
 ///
 /// ```rust,ignore
 /// let cues = vec![
-///     Cue { speaker: Some("Alice".to_string()), text: "Hello".to_string(), timestamp: Some("00:00:01.000".to_string()) },
-///     Cue { speaker: Some("Alice".to_string()), text: "How are you?".to_string(), timestamp: Some("00:00:02.000".to_string()) },
-///     Cue { speaker: Some("Bob".to_string()), text: "I'm fine.".to_string(), timestamp: Some("00:00:03.000".to_string()) },
+///     Cue { speaker: Some("Alice".to_string()), text: "Hello".to_string(), timestamp: Some("00:00:01.000".to_string()), end_timestamp: None, id: None, settings: None },
+///     Cue { speaker: Some("Alice".to_string()), text: "How are you?".to_string(), timestamp: Some("00:00:02.000".to_string()), end_timestamp: None, id: None, settings: None },
+///     Cue { speaker: Some("Bob".to_string()), text: "I'm fine.".to_string(), timestamp: Some("00:00:03.000".to_string()), end_timestamp: None, id: None, settings: None },
 /// ];
-/// let segments = consolidate_cues(&cues, "Unknown", TimestampMode::First);
+/// let segments = consolidate_cues(&cues, "Unknown", TimestampMode::First, None);
 /// assert_eq!(segments.len(), 2); // Alice and Bob
 /// ```
 pub fn consolidate_cues(
     cues: &[Cue],
     unknown_speaker_label: &str,
     timestamp_mode: TimestampMode,
+    max_gap: Option<u64>,
 ) -> Vec<SpeakerSegment> {
     let mut segments = Vec::new();
     let mut current_speaker: Option<String> = None;
     let mut current_texts = Vec::new();
     let mut current_timestamps = Vec::new();
+    let mut current_cues = Vec::new();
     let mut first_timestamp: Option<String> = None;
+    let mut last_end_timestamp: Option<String> = None;
+    let mut previous_end: Option<u64> = None;
 
     for cue in cues {
         // Skip empty or whitespace-only cues
@@ -90,10 +207,15 @@ pub fn consolidate_cues(
             .clone()
             .unwrap_or_else(|| unknown_speaker_label.to_string());
 
-        // Check if speaker changed
+        // Check if speaker changed, or the silence since the previous cue is too long
         let speaker_changed = current_speaker.as_ref() != Some(&speaker);
+        let start = cue.timestamp.as_deref().and_then(parse_timestamp);
+        let gap_too_long = match (max_gap, previous_end, start) {
+            (Some(max_gap), Some(end), Some(start)) => start.saturating_sub(end) > max_gap,
+            _ => false,
+        };
 
-        if speaker_changed {
+        if speaker_changed || gap_too_long {
             // Save the previous segment if it exists
             if let Some(prev_speaker) = current_speaker.take() {
                 let consolidated_text = join_texts(&current_texts);
@@ -108,6 +230,8 @@ pub fn consolidate_cues(
                     text: consolidated_text,
                     timestamp: segment_timestamp,
                     timestamps: current_timestamps.clone(),
+                    end_timestamp: last_end_timestamp.take(),
+                    cues: std::mem::take(&mut current_cues),
                 });
 
                 // Clear accumulators
@@ -122,9 +246,24 @@ pub fn consolidate_cues(
 
         // Add current cue to the segment
         current_texts.push(cue.text.clone());
+        current_cues.push(SegmentCue {
+            timestamp: cue.timestamp.clone(),
+            end_timestamp: cue.end_timestamp.clone(),
+            text: cue.text.trim().to_string(),
+            new_paragraph: false,
+        });
         if let Some(ts) = &cue.timestamp {
             current_timestamps.push(ts.clone());
         }
+        if cue.end_timestamp.is_some() {
+            last_end_timestamp = cue.end_timestamp.clone();
+        }
+        previous_end = cue
+            .end_timestamp
+            .as_deref()
+            .and_then(parse_timestamp)
+            .or(start)
+            .or(previous_end);
     }
 
     // Save the final segment
@@ -141,12 +280,228 @@ pub fn consolidate_cues(
             text: consolidated_text,
             timestamp: segment_timestamp,
             timestamps: current_timestamps,
+            end_timestamp: last_end_timestamp,
+            cues: current_cues,
         });
     }
 
     segments
 }
 
+/// Split long speaker turns into paragraphs.
+///
+/// Marks the cues that start a new paragraph (see [`ParagraphRules`]) and rebuilds each
+/// segment's text with a blank line between paragraphs. Segments without cue
+/// boundaries are left as they are.
+///
+/// # Arguments
+///
+/// * `segments` - The consolidated speaker segments
+/// * `rules` - When to start a new paragraph
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let rules = ParagraphRules { max_sentences: Some(5), ..ParagraphRules::default() };
+/// split_paragraphs(&mut segments, &rules);
+/// ```
+pub fn split_paragraphs(segments: &mut [SpeakerSegment], rules: &ParagraphRules) {
+    if *rules == ParagraphRules::default() {
+        return;
+    }
+
+    for segment in segments.iter_mut().filter(|segment| segment.cues.len() > 1) {
+        let mut sentences = 0;
+        let mut words = 0;
+        for index in 0..segment.cues.len() {
+            if index > 0 {
+                let previous = &segment.cues[index - 1];
+                let cue = &segment.cues[index];
+                let pause = previous
+                    .end_timestamp
+                    .as_deref()
+                    .or(previous.timestamp.as_deref())
+                    .and_then(parse_timestamp)
+                    .zip(cue.timestamp.as_deref().and_then(parse_timestamp))
+                    .map(|(end, start)| start.saturating_sub(end));
+                let sentence_ended = ends_sentence(&previous.text);
+                let full = rules.max_sentences.is_some_and(|max| sentences >= max)
+                    || rules.max_words.is_some_and(|max| words >= max);
+                let overfull = rules.max_words.is_some_and(|max| words >= max * 2);
+
+                if rules.max_pause.zip(pause).is_some_and(|(max, pause)| pause > max)
+                    || (full && sentence_ended)
+                    || overfull
+                {
+                    segment.cues[index].new_paragraph = true;
+                    sentences = 0;
+                    words = 0;
+                }
+            }
+
+            let text = &segment.cues[index].text;
+            sentences += count_sentences(text);
+            words += text.split_whitespace().count();
+        }
+
+        let mut paragraphs: Vec<Vec<String>> = Vec::new();
+        for (index, cue) in segment.cues.iter().enumerate() {
+            if index == 0 || cue.new_paragraph {
+                paragraphs.push(Vec::new());
+            }
+            if let Some(paragraph) = paragraphs.last_mut() {
+                paragraph.push(cue.text.clone());
+            }
+        }
+        segment.text = paragraphs
+            .iter()
+            .map(|paragraph| join_texts(paragraph))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+    }
+}
+
+/// Whether a word ends a sentence, ignoring closing quotes and brackets.
+fn is_sentence_end(word: &str) -> bool {
+    word.trim_end_matches(['"', '\'', '”', '’', ')', ']'])
+        .ends_with(['.', '?', '!', '…'])
+}
+
+/// Number of sentences ending in the text.
+fn count_sentences(text: &str) -> usize {
+    text.split_whitespace().filter(|word| is_sentence_end(word)).count()
+}
+
+/// Whether the text ends at the end of a sentence.
+fn ends_sentence(text: &str) -> bool {
+    text.split_whitespace().next_back().is_some_and(is_sentence_end)
+}
+
+/// The silence before a segment, from the end of the previous segment (or the start of
+/// its last cue, if the end is unknown) to the segment's start, in milliseconds.
+///
+/// Returns `None` for the first segment or when either time is unknown.
+pub fn pause_before(segments: &[SpeakerSegment], index: usize) -> Option<u64> {
+    let previous = segments.get(index.checked_sub(1)?)?;
+    let end = previous
+        .end_timestamp
+        .as_deref()
+        .or(previous.timestamps.last().map(String::as_str))
+        .and_then(parse_timestamp)?;
+    let start = parse_timestamp(segments.get(index)?.timestamps.first()?)?;
+
+    Some(start.saturating_sub(end))
+}
+
+/// Collect participants in order of first appearance.
+///
+/// # Arguments
+///
+/// * `segments` - The consolidated speaker segments
+///
+/// # Returns
+///
+/// One `Participant` per distinct speaker, with their turn and word counts.
+pub fn participants(segments: &[SpeakerSegment]) -> Vec<Participant<'_>> {
+    let mut participants: Vec<Participant> = Vec::new();
+
+    for segment in segments {
+        let words = segment.text.split_whitespace().count();
+        match participants
+            .iter_mut()
+            .find(|participant| participant.speaker == segment.speaker)
+        {
+            Some(participant) => {
+                participant.turns += 1;
+                participant.words += words;
+            }
+            None => participants.push(Participant {
+                speaker: &segment.speaker,
+                turns: 1,
+                words,
+            }),
+        }
+    }
+
+    participants
+}
+
+/// Stable anchor ids for speaker turns, such as `t-00-12-34-alice`.
+///
+/// Each id is made from the turn's start time (to the second) and a slug of the
+/// speaker name, so it stays the same when other turns are added or removed. A turn
+/// without a start time gets `turn-N-speaker`. Repeated ids get a `-2`, `-3`, ...
+/// suffix.
+///
+/// # Arguments
+///
+/// * `segments` - The consolidated speaker segments
+///
+/// # Returns
+///
+/// One id per segment, in order.
+pub fn anchor_ids(segments: &[SpeakerSegment]) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::with_capacity(segments.len());
+
+    for (index, segment) in segments.iter().enumerate() {
+        let speaker = slug(&unescape_markdown(&segment.speaker));
+        let base = match segment
+            .timestamps
+            .first()
+            .and_then(|timestamp| parse_timestamp(timestamp))
+        {
+            Some(millis) => {
+                let seconds = millis / 1000;
+                format!(
+                    "t-{:02}-{:02}-{:02}-{}",
+                    seconds / 3600,
+                    (seconds / 60) % 60,
+                    seconds % 60,
+                    speaker
+                )
+            }
+            None => format!("turn-{}-{}", index + 1, speaker),
+        };
+
+        ids.push(unique_anchor(&base, &ids));
+    }
+
+    ids
+}
+
+/// An anchor id not yet in `taken`, adding `-2`, `-3`, ... to `base` when needed.
+pub fn unique_anchor(base: &str, taken: &[String]) -> String {
+    let mut id = base.to_string();
+    let mut repeat = 1;
+    while taken.contains(&id) {
+        repeat += 1;
+        id = format!("{}-{}", base, repeat);
+    }
+    id
+}
+
+/// Lowercase a name and replace runs of anything but letters and digits with `-`.
+pub fn slug(name: &str) -> String {
+    let mut result = String::new();
+
+    for ch in name.chars() {
+        if ch.is_alphanumeric() {
+            result.extend(ch.to_lowercase());
+        } else if !result.is_empty() && !result.ends_with('-') {
+            result.push('-');
+        }
+    }
+    if result.ends_with('-') {
+        result.pop();
+    }
+
+    if result.is_empty() {
+        "speaker".to_string()
+    } else {
+        result
+    }
+}
+
 /// Join multiple text segments intelligently with proper spacing.
 ///
 /// This function joins text segments with single spaces, ensuring natural reading flow
@@ -192,20 +547,29 @@ mod tests {
                 speaker: Some("Alice".to_string()),
                 text: "Hello there.".to_string(),
                 timestamp: Some("00:00:01.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Alice".to_string()),
                 text: "How are you?".to_string(),
                 timestamp: Some("00:00:02.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Alice".to_string()),
                 text: "I hope you're well.".to_string(),
                 timestamp: Some("00:00:03.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
         ];
 
-        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None);
+        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None, None);
 
         assert_eq!(segments.len(), 1);
         assert_eq!(segments[0].speaker, "Alice");
@@ -223,25 +587,37 @@ mod tests {
                 speaker: Some("Alice".to_string()),
                 text: "Hello.".to_string(),
                 timestamp: Some("00:00:01.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Bob".to_string()),
                 text: "Hi Alice!".to_string(),
                 timestamp: Some("00:00:02.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Alice".to_string()),
                 text: "How are you?".to_string(),
                 timestamp: Some("00:00:03.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Bob".to_string()),
                 text: "I'm good, thanks!".to_string(),
                 timestamp: Some("00:00:04.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
         ];
 
-        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None);
+        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None, None);
 
         assert_eq!(segments.len(), 4);
         assert_eq!(segments[0].speaker, "Alice");
@@ -261,15 +637,21 @@ mod tests {
                 speaker: None,
                 text: "This has no speaker.".to_string(),
                 timestamp: Some("00:00:01.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: None,
                 text: "Neither does this.".to_string(),
                 timestamp: Some("00:00:02.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
         ];
 
-        let segments = consolidate_cues(&cues, "Narrator", TimestampMode::None);
+        let segments = consolidate_cues(&cues, "Narrator", TimestampMode::None, None);
 
         assert_eq!(segments.len(), 1);
         assert_eq!(segments[0].speaker, "Narrator");
@@ -283,20 +665,29 @@ mod tests {
                 speaker: Some("Alice".to_string()),
                 text: "Hello.".to_string(),
                 timestamp: Some("00:00:01.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Alice".to_string()),
                 text: "   ".to_string(), // Whitespace only
                 timestamp: Some("00:00:02.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Alice".to_string()),
                 text: "How are you?".to_string(),
                 timestamp: Some("00:00:03.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
         ];
 
-        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None);
+        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None, None);
 
         assert_eq!(segments.len(), 1);
         assert_eq!(segments[0].speaker, "Alice");
@@ -311,20 +702,29 @@ mod tests {
                 speaker: Some("Alice".to_string()),
                 text: "First sentence.".to_string(),
                 timestamp: None,
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Alice".to_string()),
                 text: "Second sentence.".to_string(),
                 timestamp: None,
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Alice".to_string()),
                 text: "Third sentence.".to_string(),
                 timestamp: None,
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
         ];
 
-        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None);
+        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None, None);
 
         assert_eq!(segments.len(), 1);
         // Sentences should be joined with single spaces
@@ -340,9 +740,12 @@ mod tests {
             speaker: Some("Alice".to_string()),
             text: "Hello.".to_string(),
             timestamp: Some("00:00:01.000".to_string()),
+            end_timestamp: None,
+            id: None,
+            settings: None,
         }];
 
-        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None);
+        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None, None);
 
         assert_eq!(segments.len(), 1);
         assert_eq!(segments[0].timestamp, None);
@@ -355,20 +758,29 @@ mod tests {
                 speaker: Some("Alice".to_string()),
                 text: "Hello.".to_string(),
                 timestamp: Some("00:00:01.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Alice".to_string()),
                 text: "How are you?".to_string(),
                 timestamp: Some("00:00:02.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Bob".to_string()),
                 text: "I'm fine.".to_string(),
                 timestamp: Some("00:00:03.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
         ];
 
-        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::First);
+        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::First, None);
 
         assert_eq!(segments.len(), 2);
         // First segment should have timestamp from first Alice cue
@@ -384,20 +796,29 @@ mod tests {
                 speaker: Some("Alice".to_string()),
                 text: "Hello.".to_string(),
                 timestamp: Some("00:00:01.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Alice".to_string()),
                 text: "How are you?".to_string(),
                 timestamp: Some("00:00:02.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
             Cue {
                 speaker: Some("Alice".to_string()),
                 text: "I hope you're well.".to_string(),
                 timestamp: Some("00:00:03.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
             },
         ];
 
-        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::Each);
+        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::Each, None);
 
         assert_eq!(segments.len(), 1);
         // In Each mode, timestamp field is None, but timestamps vec contains all
@@ -406,6 +827,188 @@ mod tests {
         assert_eq!(segments[0].timestamps[0], "00:00:01.000");
         assert_eq!(segments[0].timestamps[1], "00:00:02.000");
         assert_eq!(segments[0].timestamps[2], "00:00:03.000");
+
+        // Cue boundaries are kept for inline timestamps
+        assert_eq!(
+            segments[0].paragraphs(TimestampMode::Each),
+            vec![vec![
+                (None, "Hello."),
+                (Some("00:00:02.000"), "How are you?"),
+                (Some("00:00:03.000"), "I hope you're well."),
+            ]]
+        );
+        assert_eq!(
+            segments[0].paragraphs(TimestampMode::First),
+            vec![vec![(None, "Hello. How are you? I hope you're well.")]]
+        );
+    }
+
+    #[test]
+    fn test_anchor_ids() {
+        let segment = |speaker: &str, timestamps: &[&str]| SpeakerSegment {
+            speaker: speaker.to_string(),
+            text: "Hi.".to_string(),
+            timestamp: None,
+            timestamps: timestamps.iter().map(|ts| ts.to_string()).collect(),
+            end_timestamp: None,
+            cues: Vec::new(),
+        };
+        let segments = vec![
+            segment("Alice", &["00:12:34.500"]),
+            segment("Dr. Jane O'Brien", &["01:02:03.000"]),
+            segment("Alice", &["00:12:34.900"]),
+            segment("John\\*Doe", &[]),
+            segment("???", &["00:00:01.000"]),
+        ];
+
+        assert_eq!(
+            anchor_ids(&segments),
+            vec![
+                "t-00-12-34-alice",
+                "t-01-02-03-dr-jane-o-brien",
+                "t-00-12-34-alice-2",
+                "turn-4-john-doe",
+                "t-00-00-01-speaker",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_consolidate_end_timestamp() {
+        let cues = vec![
+            Cue {
+                speaker: Some("Alice".to_string()),
+                text: "Hello.".to_string(),
+                timestamp: Some("00:00:01.000".to_string()),
+                end_timestamp: Some("00:00:02.000".to_string()),
+                id: None,
+                settings: None,
+            },
+            Cue {
+                speaker: Some("Alice".to_string()),
+                text: "How are you?".to_string(),
+                timestamp: Some("00:00:02.000".to_string()),
+                end_timestamp: Some("00:00:04.500".to_string()),
+                id: None,
+                settings: None,
+            },
+            Cue {
+                speaker: Some("Bob".to_string()),
+                text: "I'm fine.".to_string(),
+                timestamp: Some("00:00:05.000".to_string()),
+                end_timestamp: None,
+                id: None,
+                settings: None,
+            },
+        ];
+
+        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None, None);
+
+        assert_eq!(segments.len(), 2);
+        // The segment ends when its last cue ends, regardless of timestamp mode
+        assert_eq!(segments[0].end_timestamp, Some("00:00:04.500".to_string()));
+        assert_eq!(segments[1].end_timestamp, None);
+    }
+
+    #[test]
+    fn test_consolidate_max_gap() {
+        let cue = |start: &str, end: &str, text: &str| Cue {
+            speaker: Some("Alice".to_string()),
+            text: text.to_string(),
+            timestamp: Some(start.to_string()),
+            end_timestamp: Some(end.to_string()),
+            id: None,
+            settings: None,
+        };
+        let cues = vec![
+            cue("00:00:01.000", "00:00:03.000", "Let's take a break."),
+            cue("00:00:33.000", "00:00:35.000", "Still here."),
+            cue("00:10:00.000", "00:10:02.000", "We're back."),
+        ];
+
+        // Without a limit, the whole monologue is one turn
+        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None, None);
+        assert_eq!(segments.len(), 1);
+
+        let segments = consolidate_cues(&cues, "Unknown", TimestampMode::None, Some(30_000));
+        let texts: Vec<&str> = segments.iter().map(|segment| segment.text.as_str()).collect();
+        assert_eq!(texts, vec!["Let's take a break. Still here.", "We're back."]);
+        assert_eq!(segments[0].end_timestamp.as_deref(), Some("00:00:35.000"));
+        assert_eq!(pause_before(&segments, 0), None);
+        assert_eq!(pause_before(&segments, 1), Some(565_000));
+    }
+
+    #[test]
+    fn test_split_paragraphs() {
+        let cue = |start: &str, end: &str, text: &str| Cue {
+            speaker: Some("Alice".to_string()),
+            text: text.to_string(),
+            timestamp: Some(start.to_string()),
+            end_timestamp: Some(end.to_string()),
+            id: None,
+            settings: None,
+        };
+        let cues = vec![
+            cue("00:00:01.000", "00:00:03.000", "Welcome. Today is about"),
+            cue("00:00:03.000", "00:00:05.000", "our roadmap."),
+            cue("00:00:05.000", "00:00:07.000", "First, the launch."),
+            cue("00:00:20.000", "00:00:22.000", "Next, hiring."),
+        ];
+        let consolidate = || consolidate_cues(&cues, "Unknown", TimestampMode::First, None);
+
+        // The sentence limit waits for the cue that ends the second sentence
+        let mut segments = consolidate();
+        let rules = ParagraphRules {
+            max_sentences: Some(2),
+            ..ParagraphRules::default()
+        };
+        split_paragraphs(&mut segments, &rules);
+        assert_eq!(
+            segments[0].text,
+            "Welcome. Today is about our roadmap.\n\nFirst, the launch. Next, hiring."
+        );
+        assert_eq!(
+            segments[0].paragraphs(TimestampMode::First),
+            vec![
+                vec![(None, "Welcome. Today is about our roadmap.")],
+                vec![(Some("00:00:05.000"), "First, the launch. Next, hiring.")],
+            ]
+        );
+        assert_eq!(
+            segments[0].paragraphs(TimestampMode::None)[1],
+            vec![(None, "First, the launch. Next, hiring.")]
+        );
+        assert_eq!(
+            segments[0].paragraphs(TimestampMode::Each)[1],
+            vec![
+                (Some("00:00:05.000"), "First, the launch."),
+                (Some("00:00:20.000"), "Next, hiring."),
+            ]
+        );
+
+        // A long pause breaks the paragraph even mid-thought
+        let mut segments = consolidate();
+        let rules = ParagraphRules {
+            max_pause: Some(10_000),
+            ..ParagraphRules::default()
+        };
+        split_paragraphs(&mut segments, &rules);
+        assert_eq!(
+            segments[0].text,
+            "Welcome. Today is about our roadmap. First, the launch.\n\nNext, hiring."
+        );
+
+        // Twice the word limit breaks even without a sentence end
+        let mut segments = consolidate();
+        let rules = ParagraphRules {
+            max_words: Some(2),
+            ..ParagraphRules::default()
+        };
+        split_paragraphs(&mut segments, &rules);
+        assert_eq!(
+            segments[0].text,
+            "Welcome. Today is about\n\nour roadmap.\n\nFirst, the launch.\n\nNext, hiring."
+        );
     }
 
     #[test]
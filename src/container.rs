@@ -0,0 +1,338 @@
+//! WebVTT subtitle tracks embedded in media containers.
+//!
+//! Recordings often carry their captions inside the media file rather than as a
+//! sidecar `.vtt`: MP4 files use `wvtt` sample entries and Matroska/WebM files use the
+//! `S_TEXT/WEBVTT` (or WebM `D_WEBVTT/*`) codecs. This module reads those tracks with a
+//! small pure-Rust demuxer, lets the caller pick one with `--track`, and turns the
+//! selected track back into WebVTT so it goes through the same [`VttDocument`] parser as
+//! every other input.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use vtt_to_md::container::{parse_media, ContainerKind};
+//!
+//! // Use the French captions from a recording with several subtitle tracks
+//! let doc = parse_media("meeting.mkv", ContainerKind::Matroska, Some("fre"))?;
+//! ```
+
+mod mkv;
+mod mp4;
+
+use crate::error::VttError;
+use crate::parser::VttDocument;
+use crate::timestamp::format_timestamp;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Media container families that can carry WebVTT subtitle tracks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerKind {
+    /// ISO base media files (MP4, MOV) with `wvtt` tracks
+    Mp4,
+    /// Matroska and WebM files with `S_TEXT/WEBVTT` or `D_WEBVTT/*` tracks
+    Matroska,
+}
+
+impl ContainerKind {
+    /// Detect the container family from the first bytes of a file.
+    pub fn sniff(head: &[u8]) -> Option<Self> {
+        if mkv::is_ebml(head) {
+            Some(ContainerKind::Matroska)
+        } else if mp4::is_iso_bmff(head) {
+            Some(ContainerKind::Mp4)
+        } else {
+            None
+        }
+    }
+}
+
+/// A WebVTT subtitle track extracted from a media container.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleTrack {
+    /// Track ID (MP4) or track number (Matroska)
+    pub id: u64,
+    /// Track language code, if set in the container (e.g. "eng", "fr-CA")
+    pub language: Option<String>,
+    /// Human-readable track name, if set in the container
+    pub name: Option<String>,
+    /// The track's cues in container order
+    pub cues: Vec<TrackCue>,
+}
+
+/// A single cue read from a subtitle track.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackCue {
+    /// Cue start time in milliseconds
+    pub start_ms: u64,
+    /// Cue end time in milliseconds
+    pub end_ms: u64,
+    /// Optional WebVTT cue identifier
+    pub id: Option<String>,
+    /// Optional WebVTT cue settings (e.g. "line:90% align:center")
+    pub settings: Option<String>,
+    /// Raw cue payload, including any `<v>` voice tags
+    pub payload: String,
+}
+
+impl SubtitleTrack {
+    /// Short description used when listing tracks, e.g. `#2 (fre, "Français")`.
+    fn describe(&self) -> String {
+        let mut details = Vec::new();
+        if let Some(ref language) = self.language {
+            details.push(language.clone());
+        }
+        if let Some(ref name) = self.name {
+            details.push(format!("\"{}\"", name));
+        }
+        if details.is_empty() {
+            format!("#{}", self.id)
+        } else {
+            format!("#{} ({})", self.id, details.join(", "))
+        }
+    }
+
+    /// Render the track as a WebVTT document.
+    fn to_webvtt(&self) -> String {
+        let mut vtt = String::from("WEBVTT\n\n");
+        for cue in &self.cues {
+            // Blank lines would end the cue early; collapse them
+            let payload: Vec<&str> = cue
+                .payload
+                .lines()
+                .map(str::trim_end)
+                .filter(|line| !line.is_empty())
+                .collect();
+            if payload.is_empty() {
+                continue;
+            }
+
+            if let Some(ref id) = cue.id {
+                vtt.push_str(id);
+                vtt.push('\n');
+            }
+            vtt.push_str(&format!(
+                "{} --> {}",
+                format_timestamp(cue.start_ms),
+                format_timestamp(cue.end_ms.max(cue.start_ms))
+            ));
+            if let Some(ref settings) = cue.settings {
+                vtt.push(' ');
+                vtt.push_str(settings);
+            }
+            vtt.push('\n');
+            vtt.push_str(&payload.join("\n"));
+            vtt.push_str("\n\n");
+        }
+        vtt
+    }
+}
+
+/// Read all WebVTT subtitle tracks from a local media file.
+pub fn read_tracks<P: AsRef<Path>>(
+    path: P,
+    kind: ContainerKind,
+) -> Result<Vec<SubtitleTrack>, VttError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| VttError::from_read_error(path, e))?;
+    let mut reader = BufReader::new(file);
+
+    match kind {
+        ContainerKind::Mp4 => mp4::read_tracks(&mut reader),
+        ContainerKind::Matroska => mkv::read_tracks(&mut reader),
+    }
+}
+
+/// Pick a subtitle track by 1-based position, track ID, language, or name.
+///
+/// With no selector, the first track is used.
+///
+/// # Errors
+///
+/// Returns `VttError::ParseError` if the file has no WebVTT tracks, or
+/// `VttError::UsageError` if the selector matches none of them.
+pub fn select_track<'a>(
+    tracks: &'a [SubtitleTrack],
+    selector: Option<&str>,
+) -> Result<&'a SubtitleTrack, VttError> {
+    if tracks.is_empty() {
+        return Err(VttError::ParseError {
+            reason: "No WebVTT subtitle tracks found in media file".to_string(),
+        });
+    }
+
+    let Some(selector) = selector.map(str::trim) else {
+        return Ok(&tracks[0]);
+    };
+
+    let matched = match selector.parse::<usize>() {
+        Ok(position) => tracks
+            .get(position.wrapping_sub(1))
+            .or_else(|| tracks.iter().find(|track| track.id == position as u64)),
+        Err(_) => tracks.iter().find(|track| {
+            let matches = |value: &Option<String>| {
+                value
+                    .as_deref()
+                    .is_some_and(|value| value.eq_ignore_ascii_case(selector))
+            };
+            matches(&track.language) || matches(&track.name)
+        }),
+    };
+
+    matched.ok_or_else(|| {
+        let available: Vec<String> = tracks
+            .iter()
+            .enumerate()
+            .map(|(index, track)| format!("{}: {}", index + 1, track.describe()))
+            .collect();
+        VttError::UsageError {
+            reason: format!(
+                "No subtitle track matches '{}'. Available tracks: {}",
+                selector,
+                available.join("; ")
+            ),
+        }
+    })
+}
+
+/// Extract a WebVTT track from a media file and parse it.
+///
+/// # Arguments
+///
+/// * `path` - Path to the MP4 or Matroska/WebM file
+/// * `kind` - The container family of the file
+/// * `track` - Optional track selector (see [`select_track`])
+pub fn parse_media<P: AsRef<Path>>(
+    path: P,
+    kind: ContainerKind,
+    track: Option<&str>,
+) -> Result<VttDocument, VttError> {
+    let tracks = read_tracks(path, kind)?;
+    let track = select_track(&tracks, track)?;
+    let mut document = VttDocument::parse_str(&track.to_webvtt())?;
+    document.metadata.language = track.language.clone();
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn track(id: u64, language: Option<&str>, name: Option<&str>) -> SubtitleTrack {
+        SubtitleTrack {
+            id,
+            language: language.map(str::to_string),
+            name: name.map(str::to_string),
+            cues: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_sniff_container_kind() {
+        assert_eq!(
+            ContainerKind::sniff(&[0x1A, 0x45, 0xDF, 0xA3, 0x01]),
+            Some(ContainerKind::Matroska)
+        );
+        assert_eq!(
+            ContainerKind::sniff(b"\0\0\0\x18ftypisom"),
+            Some(ContainerKind::Mp4)
+        );
+        assert_eq!(ContainerKind::sniff(b"WEBVTT\n\n"), None);
+    }
+
+    #[test]
+    fn test_select_track() {
+        let tracks = vec![
+            track(3, Some("eng"), Some("English")),
+            track(4, Some("fre"), Some("Français")),
+        ];
+
+        assert_eq!(select_track(&tracks, None).unwrap().id, 3);
+        assert_eq!(select_track(&tracks, Some("2")).unwrap().id, 4);
+        assert_eq!(select_track(&tracks, Some("FRE")).unwrap().id, 4);
+        assert_eq!(select_track(&tracks, Some("English")).unwrap().id, 3);
+        // Falls back to track ID when the position is out of range
+        assert_eq!(select_track(&tracks, Some("4")).unwrap().id, 4);
+
+        match select_track(&tracks, Some("deu")) {
+            Err(VttError::UsageError { reason }) => {
+                assert!(reason.contains("1: #3 (eng, \"English\")"));
+                assert!(reason.contains("2: #4 (fre, \"Français\")"));
+            }
+            other => panic!("Expected UsageError, got {:?}", other),
+        }
+
+        assert!(matches!(
+            select_track(&[], None),
+            Err(VttError::ParseError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_to_webvtt() {
+        let mut subtitle = track(1, None, None);
+        subtitle.cues = vec![
+            TrackCue {
+                start_ms: 1_000,
+                end_ms: 2_500,
+                id: Some("intro".to_string()),
+                settings: Some("align:start".to_string()),
+                payload: "<v Alice>Hello\n\nthere.".to_string(),
+            },
+            TrackCue {
+                start_ms: 3_000,
+                end_ms: 4_000,
+                id: None,
+                settings: None,
+                payload: "  ".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            subtitle.to_webvtt(),
+            "WEBVTT\n\nintro\n00:00:01.000 --> 00:00:02.500 align:start\n<v Alice>Hello\nthere.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_media_mp4() {
+        let data = mp4::tests::build_mp4(&[
+            (
+                "eng",
+                mp4::tests::wvtt_samples(&["<v Alice>Hello.", "<v Bob>Hi!"]),
+            ),
+            ("spa", mp4::tests::wvtt_samples(&["<v Alice>Hola."])),
+        ]);
+        let temp_file = std::env::temp_dir().join("test_parse_media.mp4");
+        fs::write(&temp_file, data).unwrap();
+
+        let doc = parse_media(&temp_file, ContainerKind::Mp4, None).unwrap();
+        assert!(doc.has_voice_tags);
+        assert_eq!(doc.cues.len(), 2);
+        assert_eq!(doc.cues[0].speaker, Some("Alice".to_string()));
+        assert_eq!(doc.cues[1].timestamp, Some("00:00:02.000".to_string()));
+
+        let doc = parse_media(&temp_file, ContainerKind::Mp4, Some("spa")).unwrap();
+        assert_eq!(doc.metadata.language, Some("spa".to_string()));
+        assert_eq!(doc.cues.len(), 1);
+        assert_eq!(doc.cues[0].text, "Hola.");
+
+        fs::remove_file(&temp_file).ok();
+    }
+
+    #[test]
+    fn test_parse_media_mkv() {
+        let temp_file = std::env::temp_dir().join("test_parse_media.mkv");
+        fs::write(&temp_file, mkv::tests::build_mkv()).unwrap();
+
+        let doc = parse_media(&temp_file, ContainerKind::Matroska, Some("eng")).unwrap();
+        assert_eq!(doc.cues.len(), 2);
+        assert_eq!(doc.cues[0].speaker, Some("Alice".to_string()));
+        assert_eq!(doc.cues[0].timestamp, Some("00:00:01.000".to_string()));
+        assert_eq!(doc.cues[1].speaker, Some("Bob".to_string()));
+
+        fs::remove_file(&temp_file).ok();
+    }
+}
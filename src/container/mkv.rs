@@ -0,0 +1,653 @@
+//! Matroska/WebM reader for WebVTT subtitle tracks.
+//!
+//! The file is scanned as a flat stream of EBML elements, descending only into the
+//! master elements that can contain track metadata or subtitle blocks. Both the
+//! Matroska `S_TEXT/WEBVTT` codec (payload in the block, settings in `BlockAdditional`)
+//! and the WebM `D_WEBVTT/*` codecs (identifier and settings lines before the payload)
+//! are supported.
+
+use super::{SubtitleTrack, TrackCue};
+use crate::error::VttError;
+use std::io::{Read, Seek, SeekFrom};
+
+const EBML_HEADER: u32 = 0x1A45_DFA3;
+const SEGMENT: u32 = 0x1853_8067;
+const INFO: u32 = 0x1549_A966;
+const TIMESTAMP_SCALE: u32 = 0x2A_D7B1;
+const TRACKS: u32 = 0x1654_AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const TRACK_NUMBER: u32 = 0xD7;
+const CODEC_ID: u32 = 0x86;
+const LANGUAGE: u32 = 0x22_B59C;
+const LANGUAGE_IETF: u32 = 0x22_B59D;
+const NAME: u32 = 0x536E;
+const CLUSTER: u32 = 0x1F43_B675;
+const CLUSTER_TIMESTAMP: u32 = 0xE7;
+const SIMPLE_BLOCK: u32 = 0xA3;
+const BLOCK_GROUP: u32 = 0xA0;
+const BLOCK: u32 = 0xA1;
+const BLOCK_DURATION: u32 = 0x9B;
+const BLOCK_ADDITIONS: u32 = 0x75A1;
+const BLOCK_MORE: u32 = 0xA6;
+const BLOCK_ADDITIONAL: u32 = 0xA5;
+
+/// Default `TimestampScale`: one millisecond in nanoseconds.
+const DEFAULT_TIMESTAMP_SCALE: u64 = 1_000_000;
+
+/// Master elements the scanner descends into; all others are read or skipped whole.
+const CONTAINERS: &[u32] = &[
+    SEGMENT,
+    INFO,
+    TRACKS,
+    TRACK_ENTRY,
+    CLUSTER,
+    BLOCK_GROUP,
+    BLOCK_ADDITIONS,
+    BLOCK_MORE,
+];
+
+/// Element payloads we need to read into memory.
+const LEAVES: &[u32] = &[
+    TIMESTAMP_SCALE,
+    TRACK_NUMBER,
+    CODEC_ID,
+    LANGUAGE,
+    LANGUAGE_IETF,
+    NAME,
+    CLUSTER_TIMESTAMP,
+    SIMPLE_BLOCK,
+    BLOCK,
+    BLOCK_DURATION,
+    BLOCK_ADDITIONAL,
+];
+
+/// Check whether the data starts with an EBML header.
+pub fn is_ebml(magic: &[u8]) -> bool {
+    magic.starts_with(&EBML_HEADER.to_be_bytes())
+}
+
+/// Read every WebVTT subtitle track from a Matroska or WebM file.
+pub fn read_tracks<R: Read + Seek>(reader: &mut R) -> Result<Vec<SubtitleTrack>, VttError> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let mut state = ScanState::default();
+    // Stack of (element id, end position) for the master elements we are inside
+    let mut stack: Vec<(u32, u64)> = Vec::new();
+    let mut pos = 0;
+
+    while pos < file_len {
+        // Close any master elements that end here
+        while let Some(&(id, end)) = stack.last() {
+            if pos < end {
+                break;
+            }
+            stack.pop();
+            state.close(id);
+        }
+
+        reader.seek(SeekFrom::Start(pos))?;
+        let (id, size, header_len) = read_element_header(reader, file_len - pos)?;
+        let body_start = pos + header_len;
+        let parent_end = stack.last().map_or(file_len, |&(_, end)| end);
+
+        // An unknown-size cluster ends where the next cluster begins
+        if id == CLUSTER {
+            while let Some(&(open_id, _)) = stack.last() {
+                if open_id != CLUSTER {
+                    break;
+                }
+                stack.pop();
+                state.close(CLUSTER);
+            }
+        }
+
+        let end = match size {
+            Some(size) => body_start.saturating_add(size).min(parent_end),
+            None => parent_end,
+        };
+
+        if CONTAINERS.contains(&id) {
+            state.open(id);
+            stack.push((id, end));
+            pos = body_start;
+            continue;
+        }
+
+        if LEAVES.contains(&id) {
+            if size.is_none() {
+                return Err(malformed());
+            }
+            reader.seek(SeekFrom::Start(body_start))?;
+            // Blocks of other tracks (video, audio) are skipped without being read
+            if (id == SIMPLE_BLOCK || id == BLOCK)
+                && !state.wants_block(read_block_track(reader, end - body_start)?)
+            {
+                pos = end;
+                continue;
+            }
+            // The element may claim more than its parent or the file holds
+            let mut data = vec![0u8; (end - body_start) as usize];
+            reader.seek(SeekFrom::Start(body_start))?;
+            reader.read_exact(&mut data)?;
+            state.leaf(id, &data)?;
+        }
+
+        pos = end;
+    }
+
+    while let Some((id, _)) = stack.pop() {
+        state.close(id);
+    }
+
+    state.finish()
+}
+
+/// A subtitle track entry as it is being read from `Tracks`.
+#[derive(Debug, Default)]
+struct TrackEntry {
+    number: u64,
+    codec_id: String,
+    language: Option<String>,
+    name: Option<String>,
+}
+
+/// A block as it is being read from a `BlockGroup` or `SimpleBlock`.
+#[derive(Debug, Default)]
+struct PendingBlock {
+    track: u64,
+    relative_time: i64,
+    payload: Vec<u8>,
+    duration: Option<u64>,
+    additional: Option<String>,
+}
+
+/// Accumulated state while scanning the element stream.
+#[derive(Debug, Default)]
+struct ScanState {
+    timestamp_scale: Option<u64>,
+    entries: Vec<TrackEntry>,
+    current_entry: Option<TrackEntry>,
+    cluster_time: u64,
+    pending_block: Option<PendingBlock>,
+    /// Blocks with their cluster timestamp, resolved once the timestamp scale is known
+    blocks: Vec<(u64, PendingBlock)>,
+}
+
+impl ScanState {
+    fn open(&mut self, id: u32) {
+        match id {
+            TRACK_ENTRY => self.current_entry = Some(TrackEntry::default()),
+            CLUSTER => self.cluster_time = 0,
+            BLOCK_GROUP => self.pending_block = None,
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, id: u32) {
+        match id {
+            TRACK_ENTRY => {
+                if let Some(entry) = self.current_entry.take() {
+                    self.entries.push(entry);
+                }
+            }
+            BLOCK_GROUP => {
+                if let Some(block) = self.pending_block.take()
+                    && self.wants_block(block.track)
+                {
+                    self.push_block(block);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether blocks of the given track number belong to a WebVTT track.
+    ///
+    /// `Tracks` comes before the first `Cluster`, so the subtitle tracks are known by
+    /// the time blocks are read.
+    fn wants_block(&self, track: u64) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.number == track && is_webvtt_codec(&entry.codec_id))
+    }
+
+    fn leaf(&mut self, id: u32, data: &[u8]) -> Result<(), VttError> {
+        match id {
+            TIMESTAMP_SCALE => self.timestamp_scale = Some(read_uint(data)),
+            CLUSTER_TIMESTAMP => self.cluster_time = read_uint(data),
+            TRACK_NUMBER | CODEC_ID | LANGUAGE | LANGUAGE_IETF | NAME => {
+                if let Some(entry) = self.current_entry.as_mut() {
+                    let text = || {
+                        String::from_utf8_lossy(data)
+                            .trim_end_matches('\0')
+                            .to_string()
+                    };
+                    match id {
+                        TRACK_NUMBER => entry.number = read_uint(data),
+                        CODEC_ID => entry.codec_id = text(),
+                        // Prefer the IETF tag over the legacy ISO-639-2 code
+                        LANGUAGE_IETF => entry.language = Some(text()),
+                        LANGUAGE => {
+                            entry.language.get_or_insert_with(text);
+                        }
+                        _ => entry.name = Some(text()),
+                    }
+                }
+            }
+            SIMPLE_BLOCK => {
+                let block = parse_block(data)?;
+                self.push_block(block);
+            }
+            BLOCK => {
+                let block = parse_block(data)?;
+                let pending = self.pending_block.get_or_insert_with(PendingBlock::default);
+                pending.track = block.track;
+                pending.relative_time = block.relative_time;
+                pending.payload = block.payload;
+            }
+            BLOCK_DURATION => {
+                self.pending_block
+                    .get_or_insert_with(PendingBlock::default)
+                    .duration = Some(read_uint(data));
+            }
+            BLOCK_ADDITIONAL => {
+                self.pending_block
+                    .get_or_insert_with(PendingBlock::default)
+                    .additional = Some(String::from_utf8_lossy(data).into_owned());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn push_block(&mut self, block: PendingBlock) {
+        self.blocks.push((self.cluster_time, block));
+    }
+
+    fn finish(self) -> Result<Vec<SubtitleTrack>, VttError> {
+        let scale = self.timestamp_scale.unwrap_or(DEFAULT_TIMESTAMP_SCALE);
+        let to_ms = |ticks: i64| (ticks.max(0) as u64).saturating_mul(scale) / 1_000_000;
+
+        let mut tracks: Vec<(bool, SubtitleTrack)> = self
+            .entries
+            .into_iter()
+            .filter(|entry| is_webvtt_codec(&entry.codec_id))
+            .map(|entry| {
+                let webm_layout = entry.codec_id.starts_with("D_WEBVTT");
+                let language = entry.language.filter(|lang| lang != "und");
+                (
+                    webm_layout,
+                    SubtitleTrack {
+                        id: entry.number,
+                        language,
+                        name: entry.name,
+                        cues: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+
+        for (cluster_time, block) in self.blocks {
+            let Some((webm_layout, track)) = tracks.iter_mut().find(|(_, t)| t.id == block.track)
+            else {
+                continue;
+            };
+
+            // Crafted cluster timestamps or durations must not wrap around
+            let start_ticks = i64::try_from(cluster_time)
+                .ok()
+                .and_then(|time| time.checked_add(block.relative_time))
+                .ok_or_else(malformed)?;
+            let start_ms = to_ms(start_ticks);
+            let end_ms = match block.duration {
+                Some(duration) => i64::try_from(duration)
+                    .ok()
+                    .and_then(|duration| start_ticks.checked_add(duration))
+                    .map(to_ms)
+                    .ok_or_else(malformed)?,
+                None => start_ms,
+            };
+            let data = String::from_utf8_lossy(&block.payload).into_owned();
+
+            let cue = if *webm_layout {
+                // WebM: identifier line, settings line, then the payload
+                let mut lines = data.splitn(3, '\n');
+                let id = lines.next().unwrap_or("").trim().to_string();
+                let settings = lines.next().unwrap_or("").trim().to_string();
+                TrackCue {
+                    start_ms,
+                    end_ms,
+                    id: (!id.is_empty()).then_some(id),
+                    settings: (!settings.is_empty()).then_some(settings),
+                    payload: lines.next().unwrap_or("").to_string(),
+                }
+            } else {
+                // Matroska: BlockAdditional holds settings, identifier and comments
+                let mut additional = block.additional.as_deref().unwrap_or("").lines();
+                let settings = additional.next().unwrap_or("").trim().to_string();
+                let id = additional.next().unwrap_or("").trim().to_string();
+                TrackCue {
+                    start_ms,
+                    end_ms,
+                    id: (!id.is_empty()).then_some(id),
+                    settings: (!settings.is_empty()).then_some(settings),
+                    payload: data,
+                }
+            };
+            track.cues.push(cue);
+        }
+
+        Ok(tracks.into_iter().map(|(_, track)| track).collect())
+    }
+}
+
+fn is_webvtt_codec(codec_id: &str) -> bool {
+    codec_id == "S_TEXT/WEBVTT" || codec_id.starts_with("D_WEBVTT/")
+}
+
+/// Parse a `Block`/`SimpleBlock` body: track number, relative timestamp, flags, data.
+fn parse_block(data: &[u8]) -> Result<PendingBlock, VttError> {
+    let (track, len) = read_vint(data).ok_or_else(malformed)?;
+    let header = data.get(len..len + 3).ok_or_else(malformed)?;
+    let relative_time = i16::from_be_bytes([header[0], header[1]]) as i64;
+    // Lacing is not used for subtitle tracks
+    if header[2] & 0x06 != 0 {
+        return Err(VttError::ParseError {
+            reason: "Laced Matroska subtitle blocks are not supported".to_string(),
+        });
+    }
+
+    Ok(PendingBlock {
+        track: track.ok_or_else(malformed)?,
+        relative_time,
+        payload: data[len + 3..].to_vec(),
+        duration: None,
+        additional: None,
+    })
+}
+
+/// Read the track number at the start of a `Block`/`SimpleBlock` body.
+///
+/// Returns 0, which no track uses, when the track number is reserved or unreadable.
+fn read_block_track<R: Read>(reader: &mut R, remaining: u64) -> Result<u64, VttError> {
+    let mut buf = [0u8; 8];
+    let available = remaining.min(buf.len() as u64) as usize;
+    reader.read_exact(&mut buf[..available])?;
+    Ok(read_vint(&buf[..available])
+        .and_then(|(track, _)| track)
+        .unwrap_or(0))
+}
+
+/// Read an element header, returning (id, size, header length).
+///
+/// A size of `None` means the element has unknown size. The reader is left at an
+/// unspecified position; callers seek to the body explicitly.
+fn read_element_header<R: Read>(
+    reader: &mut R,
+    remaining: u64,
+) -> Result<(u32, Option<u64>, u64), VttError> {
+    let mut buf = [0u8; 12];
+    let available = remaining.min(buf.len() as u64) as usize;
+    reader.read_exact(&mut buf[..available])?;
+
+    let id_len = vint_length(buf[0])
+        .filter(|&len| len <= 4 && len <= available)
+        .ok_or_else(malformed)?;
+    let id = buf[..id_len]
+        .iter()
+        .fold(0u32, |acc, &b| (acc << 8) | u32::from(b));
+    let (size, size_len) = read_vint(&buf[id_len..available]).ok_or_else(malformed)?;
+
+    Ok((id, size, (id_len + size_len) as u64))
+}
+
+/// Length in bytes of a variable-size integer, from its first byte.
+fn vint_length(first: u8) -> Option<usize> {
+    (first != 0).then(|| first.leading_zeros() as usize + 1)
+}
+
+/// Read a variable-size integer with its length marker removed.
+///
+/// Returns `(None, len)` for the reserved all-ones value (unknown size).
+fn read_vint(data: &[u8]) -> Option<(Option<u64>, usize)> {
+    let len = vint_length(*data.first()?)?;
+    let bytes = data.get(..len)?;
+    let mask = if len >= 8 { 0 } else { 0xFFu8 >> len };
+    let value = bytes[1..]
+        .iter()
+        .fold(u64::from(bytes[0] & mask), |acc, &b| {
+            (acc << 8) | u64::from(b)
+        });
+    let all_ones = (1u64 << (7 * len)) - 1;
+    Some(((value != all_ones).then_some(value), len))
+}
+
+fn read_uint(data: &[u8]) -> u64 {
+    data.iter().fold(0, |acc, &b| (acc << 8) | u64::from(b))
+}
+
+fn malformed() -> VttError {
+    VttError::ParseError {
+        reason: "Malformed Matroska element".to_string(),
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    pub fn element(id: u32, body: &[u8]) -> Vec<u8> {
+        let mut data: Vec<u8> = id
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|&b| b == 0)
+            .collect();
+        // Always use an 8-byte size so test builders don't need to pick a width
+        data.push(0x01);
+        data.extend_from_slice(&(body.len() as u64).to_be_bytes()[1..]);
+        data.extend_from_slice(body);
+        data
+    }
+
+    fn block(track: u8, relative_time: i16, payload: &str) -> Vec<u8> {
+        let mut data = vec![0x80 | track];
+        data.extend(relative_time.to_be_bytes());
+        data.push(0);
+        data.extend(payload.as_bytes());
+        data
+    }
+
+    fn track_entry(number: u8, codec: &str, language: &str) -> Vec<u8> {
+        element(
+            TRACK_ENTRY,
+            &[
+                element(TRACK_NUMBER, &[number]),
+                element(CODEC_ID, codec.as_bytes()),
+                element(LANGUAGE, language.as_bytes()),
+            ]
+            .concat(),
+        )
+    }
+
+    /// Build a minimal Matroska file with an English and a French WebVTT track.
+    pub fn build_mkv() -> Vec<u8> {
+        let tracks = element(
+            TRACKS,
+            &[
+                track_entry(1, "S_TEXT/WEBVTT", "eng"),
+                track_entry(2, "D_WEBVTT/SUBTITLES", "fre"),
+                track_entry(3, "V_VP9", "und"),
+            ]
+            .concat(),
+        );
+        let cluster = element(
+            CLUSTER,
+            &[
+                element(CLUSTER_TIMESTAMP, &[0x03, 0xE8]),
+                element(
+                    BLOCK_GROUP,
+                    &[
+                        element(BLOCK, &block(1, 0, "<v Alice>Hello there.")),
+                        element(BLOCK_DURATION, &[0x07, 0xD0]),
+                    ]
+                    .concat(),
+                ),
+                element(
+                    BLOCK_GROUP,
+                    &[
+                        element(BLOCK, &block(2, 500, "cue-1\nline:90%\n<v Alice>Bonjour.")),
+                        element(BLOCK_DURATION, &[0x05, 0xDC]),
+                    ]
+                    .concat(),
+                ),
+                element(SIMPLE_BLOCK, &block(1, 3000, "<v Bob>Hi Alice!")),
+            ]
+            .concat(),
+        );
+
+        [
+            element(EBML_HEADER, &[]),
+            element(
+                SEGMENT,
+                &[
+                    element(INFO, &element(TIMESTAMP_SCALE, &[0x0F, 0x42, 0x40])),
+                    tracks,
+                    cluster,
+                ]
+                .concat(),
+            ),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_read_webvtt_tracks() {
+        let tracks = read_tracks(&mut Cursor::new(build_mkv())).unwrap();
+        assert_eq!(tracks.len(), 2);
+
+        assert_eq!(tracks[0].id, 1);
+        assert_eq!(tracks[0].language.as_deref(), Some("eng"));
+        assert_eq!(tracks[0].cues.len(), 2);
+        assert_eq!(
+            (tracks[0].cues[0].start_ms, tracks[0].cues[0].end_ms),
+            (1000, 3000)
+        );
+        assert_eq!(tracks[0].cues[0].payload, "<v Alice>Hello there.");
+        // SimpleBlocks carry no duration
+        assert_eq!(
+            (tracks[0].cues[1].start_ms, tracks[0].cues[1].end_ms),
+            (4000, 4000)
+        );
+
+        assert_eq!(tracks[1].language.as_deref(), Some("fre"));
+        let cue = &tracks[1].cues[0];
+        assert_eq!((cue.start_ms, cue.end_ms), (1500, 3000));
+        assert_eq!(cue.id.as_deref(), Some("cue-1"));
+        assert_eq!(cue.settings.as_deref(), Some("line:90%"));
+        assert_eq!(cue.payload, "<v Alice>Bonjour.");
+    }
+
+    #[test]
+    fn test_truncated_file_is_malformed() {
+        let data = [0x1A, 0x45, 0xDF, 0xA3, 0x80, 0x1A, 0x45];
+        let err = read_tracks(&mut Cursor::new(data)).unwrap_err();
+        assert!(matches!(err, VttError::ParseError { .. }));
+    }
+
+    #[test]
+    fn test_skips_blocks_of_other_tracks() {
+        let tracks = element(
+            TRACKS,
+            &[
+                track_entry(1, "S_TEXT/WEBVTT", "eng"),
+                track_entry(2, "A_OPUS", "eng"),
+            ]
+            .concat(),
+        );
+        // Xiph-laced audio block, which the subtitle reader could not parse
+        let mut audio = block(2, 0, "\x01\x02audio");
+        audio[3] = 0x02;
+        // A subtitle block claiming far more data than the file holds
+        let mut oversized = vec![SIMPLE_BLOCK as u8, 0x01, 0x00, 0x00, 0x10];
+        oversized.extend_from_slice(&[0; 4]);
+        oversized.extend(block(1, 2000, "<v Bob>Cut off"));
+        let cluster = element(
+            CLUSTER,
+            &[
+                element(CLUSTER_TIMESTAMP, &[0x00]),
+                element(SIMPLE_BLOCK, &audio),
+                element(SIMPLE_BLOCK, &block(1, 1000, "<v Alice>Hello.")),
+                oversized,
+            ]
+            .concat(),
+        );
+        let data = [
+            element(EBML_HEADER, &[]),
+            element(SEGMENT, &[tracks, cluster].concat()),
+        ]
+        .concat();
+
+        let tracks = read_tracks(&mut Cursor::new(data)).unwrap();
+        assert_eq!(tracks.len(), 1);
+        let payloads: Vec<&str> = tracks[0]
+            .cues
+            .iter()
+            .map(|cue| cue.payload.as_str())
+            .collect();
+        assert_eq!(payloads, vec!["<v Alice>Hello.", "<v Bob>Cut off"]);
+    }
+
+    #[test]
+    fn test_block_time_overflow_is_malformed() {
+        let huge_start = [
+            element(CLUSTER_TIMESTAMP, &(i64::MAX as u64).to_be_bytes()),
+            element(SIMPLE_BLOCK, &block(1, 1000, "<v Alice>Hello.")),
+        ];
+        let huge_duration = [
+            element(CLUSTER_TIMESTAMP, &[0x00]),
+            element(
+                BLOCK_GROUP,
+                &[
+                    element(BLOCK, &block(1, 1000, "<v Alice>Hello.")),
+                    element(BLOCK_DURATION, &u64::MAX.to_be_bytes()),
+                ]
+                .concat(),
+            ),
+        ];
+
+        for cluster in [huge_start, huge_duration] {
+            let tracks = element(TRACKS, &track_entry(1, "S_TEXT/WEBVTT", "eng"));
+            let data = [
+                element(EBML_HEADER, &[]),
+                element(
+                    SEGMENT,
+                    &[tracks, element(CLUSTER, &cluster.concat())].concat(),
+                ),
+            ]
+            .concat();
+
+            let err = read_tracks(&mut Cursor::new(data)).unwrap_err();
+            assert!(matches!(err, VttError::ParseError { .. }));
+        }
+    }
+
+    #[test]
+    fn test_read_vint() {
+        assert_eq!(read_vint(&[0x81]), Some((Some(1), 1)));
+        assert_eq!(read_vint(&[0x40, 0x02]), Some((Some(2), 2)));
+        assert_eq!(read_vint(&[0xFF]), Some((None, 1)));
+        assert_eq!(
+            read_vint(&[0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]),
+            Some((None, 8))
+        );
+        assert_eq!(read_vint(&[0x00]), None);
+    }
+
+    #[test]
+    fn test_is_ebml() {
+        assert!(is_ebml(&[0x1A, 0x45, 0xDF, 0xA3, 0x00]));
+        assert!(!is_ebml(b"\0\0\0\x18ftyp"));
+    }
+}
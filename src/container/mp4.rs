@@ -0,0 +1,598 @@
+//! ISO base media file (MP4/MOV) reader for `wvtt` subtitle tracks.
+//!
+//! Only the boxes needed to locate WebVTT samples are decoded: `moov/trak` for track
+//! metadata and the `stbl` sample tables (`stts`, `stsc`, `stsz`, `stco`/`co64`) for
+//! sample timing and file offsets. Each sample is a sequence of `vttc` cue boxes (or a
+//! `vtte` empty box for gaps) as defined by ISO/IEC 14496-30.
+
+use super::{SubtitleTrack, TrackCue};
+use crate::error::VttError;
+use std::io::{Read, Seek, SeekFrom};
+
+/// Top-level box types that can start an ISO base media file.
+const LEADING_BOX_TYPES: &[&[u8; 4]] = &[
+    b"ftyp", b"styp", b"moov", b"mdat", b"free", b"skip", b"wide",
+];
+
+/// Check whether the data starts with an ISO base media box header.
+pub fn is_iso_bmff(head: &[u8]) -> bool {
+    head.get(4..8).is_some_and(|kind| {
+        LEADING_BOX_TYPES
+            .iter()
+            .any(|known| kind == known.as_slice())
+    })
+}
+
+/// Read every `wvtt` subtitle track from an MP4 file.
+///
+/// Fragmented MP4 files (where samples live in `moof` boxes) are not supported; their
+/// subtitle tracks are reported with no cues.
+pub fn read_tracks<R: Read + Seek>(reader: &mut R) -> Result<Vec<SubtitleTrack>, VttError> {
+    let moov = read_top_level_box(reader, b"moov")?.ok_or_else(|| VttError::ParseError {
+        reason: "MP4 file has no movie (moov) box".to_string(),
+    })?;
+
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    let mut tracks = Vec::new();
+    for (kind, trak) in Boxes::new(&moov) {
+        if &kind != b"trak" {
+            continue;
+        }
+        if let Some(track) = read_track(reader, trak, file_len)? {
+            tracks.push(track);
+        }
+    }
+
+    Ok(tracks)
+}
+
+/// Scan top-level boxes and return the body of the first one with the given type.
+fn read_top_level_box<R: Read + Seek>(
+    reader: &mut R,
+    wanted: &[u8; 4],
+) -> Result<Option<Vec<u8>>, VttError> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    let mut pos = 0;
+
+    while pos + 8 <= file_len {
+        reader.seek(SeekFrom::Start(pos))?;
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+        let size32 = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let kind = [header[4], header[5], header[6], header[7]];
+        let truncated = || VttError::ParseError {
+            reason: format!("Truncated MP4 box '{}'", String::from_utf8_lossy(&kind)),
+        };
+
+        let (header_len, size) = match size32 {
+            0 => (8, file_len - pos),
+            1 => {
+                // The 64-bit size follows the header
+                if pos + 16 > file_len {
+                    return Err(truncated());
+                }
+                let mut large = [0u8; 8];
+                reader.read_exact(&mut large)?;
+                (16, u64::from_be_bytes(large))
+            }
+            size => (8, size),
+        };
+
+        let Some(end) = pos.checked_add(size) else {
+            return Err(malformed(&String::from_utf8_lossy(&kind)));
+        };
+        if size < header_len || end > file_len {
+            return Err(truncated());
+        }
+
+        if &kind == wanted {
+            let mut body = vec![0u8; (size - header_len) as usize];
+            reader.read_exact(&mut body)?;
+            return Ok(Some(body));
+        }
+
+        pos = end;
+    }
+
+    Ok(None)
+}
+
+/// Decode a `trak` box, returning `None` if it is not a WebVTT track.
+fn read_track<R: Read + Seek>(
+    reader: &mut R,
+    trak: &[u8],
+    file_len: u64,
+) -> Result<Option<SubtitleTrack>, VttError> {
+    let Some(mdia) = find_box(trak, b"mdia") else {
+        return Ok(None);
+    };
+    let Some(stbl) = find_path(mdia, &[b"minf", b"stbl"]) else {
+        return Ok(None);
+    };
+    let is_wvtt = find_box(stbl, b"stsd")
+        .and_then(|stsd| Boxes::new(stsd.get(8..)?).next())
+        .is_some_and(|(kind, _)| &kind == b"wvtt");
+    if !is_wvtt {
+        return Ok(None);
+    }
+
+    let id = find_box(trak, b"tkhd").and_then(track_id).unwrap_or(0) as u64;
+    let (timescale, language) = find_box(mdia, b"mdhd")
+        .and_then(media_header)
+        .ok_or_else(|| malformed("mdhd"))?;
+    let name = find_box(mdia, b"hdlr").and_then(handler_name);
+
+    let mut cues = Vec::new();
+    for sample in sample_table(stbl, file_len)? {
+        let in_file = sample
+            .offset
+            .checked_add(u64::from(sample.size))
+            .is_some_and(|end| end <= file_len);
+        if !in_file {
+            return Err(malformed("stco"));
+        }
+        let mut data = vec![0u8; sample.size as usize];
+        reader.seek(SeekFrom::Start(sample.offset))?;
+        reader.read_exact(&mut data)?;
+
+        let to_ms = |ticks: u64| ticks.saturating_mul(1000) / timescale;
+        let start_ms = to_ms(sample.decode_time);
+        let end_ms = to_ms(sample.decode_time.saturating_add(sample.duration));
+        cues.extend(sample_cues(&data, start_ms, end_ms));
+    }
+
+    Ok(Some(SubtitleTrack {
+        id,
+        language,
+        name,
+        cues,
+    }))
+}
+
+/// A single sample's location and timing, resolved from the sample tables.
+#[derive(Debug, PartialEq)]
+struct Sample {
+    offset: u64,
+    size: u32,
+    decode_time: u64,
+    duration: u64,
+}
+
+/// Resolve sample offsets, sizes and times from an `stbl` box.
+///
+/// Counts are checked against the box lengths and `file_len` before anything is
+/// allocated from them, so a malformed table cannot request an oversized buffer.
+fn sample_table(stbl: &[u8], file_len: u64) -> Result<Vec<Sample>, VttError> {
+    // stsz: sample sizes, either one fixed size or a table
+    let stsz = find_box(stbl, b"stsz").ok_or_else(|| malformed("stsz"))?;
+    let fixed_size = be_u32(stsz, 4).ok_or_else(|| malformed("stsz"))?;
+    let sample_count = be_u32(stsz, 8).ok_or_else(|| malformed("stsz"))? as usize;
+    let fits = if fixed_size != 0 {
+        sample_count as u64 * u64::from(fixed_size) <= file_len
+    } else {
+        table_fits(stsz, 12, sample_count, 4)
+    };
+    if !fits {
+        return Err(malformed("stsz"));
+    }
+    let size_at = |index: usize| match fixed_size {
+        _ if index >= sample_count => None,
+        0 => be_u32(stsz, 12 + index * 4),
+        size => Some(size),
+    };
+
+    // stco/co64: chunk offsets
+    let chunk_offsets: Vec<u64> = if let Some(stco) = find_box(stbl, b"stco") {
+        let count = be_u32(stco, 4).ok_or_else(|| malformed("stco"))? as usize;
+        if !table_fits(stco, 8, count, 4) {
+            return Err(malformed("stco"));
+        }
+        (0..count)
+            .map(|i| be_u32(stco, 8 + i * 4).map(u64::from))
+            .collect::<Option<_>>()
+            .ok_or_else(|| malformed("stco"))?
+    } else if let Some(co64) = find_box(stbl, b"co64") {
+        let count = be_u32(co64, 4).ok_or_else(|| malformed("co64"))? as usize;
+        if !table_fits(co64, 8, count, 8) {
+            return Err(malformed("co64"));
+        }
+        (0..count)
+            .map(|i| be_u64(co64, 8 + i * 8))
+            .collect::<Option<_>>()
+            .ok_or_else(|| malformed("co64"))?
+    } else {
+        Vec::new()
+    };
+
+    // stsc: (first_chunk, samples_per_chunk) runs
+    let stsc = find_box(stbl, b"stsc").ok_or_else(|| malformed("stsc"))?;
+    let run_count = be_u32(stsc, 4).ok_or_else(|| malformed("stsc"))? as usize;
+    if !table_fits(stsc, 8, run_count, 12) {
+        return Err(malformed("stsc"));
+    }
+    let runs: Vec<(u32, u32)> = (0..run_count)
+        .map(|i| Some((be_u32(stsc, 8 + i * 12)?, be_u32(stsc, 12 + i * 12)?)))
+        .collect::<Option<_>>()
+        .ok_or_else(|| malformed("stsc"))?;
+
+    // stts: (sample_count, sample_delta) runs, walked alongside the samples
+    let stts = find_box(stbl, b"stts").ok_or_else(|| malformed("stts"))?;
+    let delta_count = be_u32(stts, 4).ok_or_else(|| malformed("stts"))? as usize;
+    if !table_fits(stts, 8, delta_count, 8) {
+        return Err(malformed("stts"));
+    }
+    let mut deltas = (0..delta_count).flat_map(|i| {
+        let count = be_u32(stts, 8 + i * 8).unwrap_or(0);
+        let delta = be_u32(stts, 12 + i * 8).unwrap_or(0);
+        std::iter::repeat_n(u64::from(delta), count as usize)
+    });
+
+    let mut samples = Vec::new();
+    let mut decode_time: u64 = 0;
+    for (chunk_index, &chunk_offset) in chunk_offsets.iter().enumerate() {
+        let chunk_number = chunk_index as u32 + 1;
+        let samples_in_chunk = runs
+            .iter()
+            .rev()
+            .find(|(first_chunk, _)| *first_chunk <= chunk_number)
+            .map(|(_, per_chunk)| *per_chunk)
+            .unwrap_or(0);
+
+        let mut offset = chunk_offset;
+        for _ in 0..samples_in_chunk {
+            let (Some(size), Some(duration)) = (size_at(samples.len()), deltas.next()) else {
+                return Ok(samples);
+            };
+            samples.push(Sample {
+                offset,
+                size,
+                decode_time,
+                duration,
+            });
+            offset = offset.saturating_add(u64::from(size));
+            decode_time = decode_time.saturating_add(duration);
+        }
+    }
+
+    Ok(samples)
+}
+
+/// Whether a full box holds `count` entries of `entry_len` bytes starting at `start`.
+fn table_fits(body: &[u8], start: usize, count: usize, entry_len: usize) -> bool {
+    count
+        .checked_mul(entry_len)
+        .and_then(|len| len.checked_add(start))
+        .is_some_and(|end| end <= body.len())
+}
+
+/// Decode the cue boxes inside a `wvtt` sample.
+fn sample_cues(data: &[u8], start_ms: u64, end_ms: u64) -> Vec<TrackCue> {
+    Boxes::new(data)
+        .filter(|(kind, _)| kind == b"vttc")
+        .map(|(_, vttc)| {
+            let text = |kind: &[u8; 4]| {
+                find_box(vttc, kind).map(|body| String::from_utf8_lossy(body).into_owned())
+            };
+            TrackCue {
+                start_ms,
+                end_ms,
+                id: text(b"iden"),
+                settings: text(b"sttg"),
+                payload: text(b"payl").unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// Read the track ID from a `tkhd` box.
+fn track_id(tkhd: &[u8]) -> Option<u32> {
+    match *tkhd.first()? {
+        1 => be_u32(tkhd, 20),
+        _ => be_u32(tkhd, 12),
+    }
+}
+
+/// Read the timescale and ISO-639-2 language from an `mdhd` box.
+fn media_header(mdhd: &[u8]) -> Option<(u64, Option<String>)> {
+    let (timescale, language) = match *mdhd.first()? {
+        1 => (be_u32(mdhd, 20)?, be_u16(mdhd, 32)?),
+        _ => (be_u32(mdhd, 12)?, be_u16(mdhd, 20)?),
+    };
+    if timescale == 0 {
+        return None;
+    }
+
+    // Three 5-bit characters, each offset from 0x60
+    let code: String = [10, 5, 0]
+        .iter()
+        .map(|shift| (((language >> shift) & 0x1f) as u8 + 0x60) as char)
+        .collect();
+    let language = (code.chars().all(|c| c.is_ascii_lowercase()) && code != "und").then_some(code);
+
+    Some((u64::from(timescale), language))
+}
+
+/// Read the human-readable name from an `hdlr` box.
+fn handler_name(hdlr: &[u8]) -> Option<String> {
+    let name = hdlr.get(24..)?;
+    let name = name.split(|&b| b == 0).next()?;
+    let name = String::from_utf8_lossy(name).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+fn malformed(kind: &str) -> VttError {
+    VttError::ParseError {
+        reason: format!("Malformed MP4 '{}' box", kind),
+    }
+}
+
+fn find_box<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    Boxes::new(data)
+        .find(|(k, _)| k == kind)
+        .map(|(_, body)| body)
+}
+
+fn find_path<'a>(data: &'a [u8], path: &[&[u8; 4]]) -> Option<&'a [u8]> {
+    path.iter()
+        .try_fold(data, |data, kind| find_box(data, kind))
+}
+
+fn be_u16(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn be_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+fn be_u64(data: &[u8], pos: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(data.get(pos..pos + 8)?.try_into().ok()?))
+}
+
+/// Iterator over the child boxes of an in-memory box body.
+///
+/// Stops at the first truncated or malformed box header.
+struct Boxes<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Boxes<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Boxes { data }
+    }
+}
+
+impl<'a> Iterator for Boxes<'a> {
+    type Item = ([u8; 4], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let size32 = be_u32(self.data, 0)? as usize;
+        let kind: [u8; 4] = self.data.get(4..8)?.try_into().ok()?;
+        let (header_len, size) = match size32 {
+            0 => (8, self.data.len()),
+            1 => (16, usize::try_from(be_u64(self.data, 8)?).ok()?),
+            size => (8, size),
+        };
+        if size < header_len || size > self.data.len() {
+            self.data = &[];
+            return None;
+        }
+
+        let body = &self.data[header_len..size];
+        self.data = &self.data[size..];
+        Some((kind, body))
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    pub fn mp4_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut data = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(kind);
+        data.extend_from_slice(body);
+        data
+    }
+
+    fn full_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 0];
+        data.extend_from_slice(body);
+        mp4_box(kind, &data)
+    }
+
+    fn u32s(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    fn vttc(speaker_text: &str) -> Vec<u8> {
+        mp4_box(b"vttc", &mp4_box(b"payl", speaker_text.as_bytes()))
+    }
+
+    fn trak(
+        track_id: u32,
+        language: &str,
+        entry: &[u8; 4],
+        samples: &[Vec<u8>],
+        mdat_offset: u32,
+    ) -> Vec<u8> {
+        let mut tkhd = vec![0u8; 8];
+        tkhd.extend(u32s(&[track_id]));
+        tkhd.extend(vec![0u8; 68]);
+
+        let lang = language
+            .bytes()
+            .fold(0u16, |acc, c| (acc << 5) | u16::from(c - 0x60));
+        let mut mdhd = u32s(&[0, 0, 1000, 0]);
+        mdhd.extend(lang.to_be_bytes());
+        mdhd.extend([0, 0]);
+
+        let mut hdlr = u32s(&[0]);
+        hdlr.extend(b"text");
+        hdlr.extend(vec![0u8; 12]);
+        hdlr.extend(b"Captions\0");
+
+        let mut stsd = u32s(&[1]);
+        stsd.extend(mp4_box(entry, &[0u8; 8]));
+
+        let sizes: Vec<u32> = samples.iter().map(|s| s.len() as u32).collect();
+        let mut stsz = u32s(&[0, sizes.len() as u32]);
+        stsz.extend(u32s(&sizes));
+
+        let stbl = [
+            full_box(b"stsd", &stsd),
+            full_box(b"stts", &u32s(&[1, samples.len() as u32, 2000])),
+            full_box(b"stsc", &u32s(&[1, 1, samples.len() as u32, 1])),
+            full_box(b"stsz", &stsz),
+            full_box(b"stco", &u32s(&[1, mdat_offset])),
+        ]
+        .concat();
+
+        let mdia = [
+            full_box(b"mdhd", &mdhd),
+            full_box(b"hdlr", &hdlr),
+            mp4_box(b"minf", &mp4_box(b"stbl", &stbl)),
+        ]
+        .concat();
+
+        mp4_box(
+            b"trak",
+            &[full_box(b"tkhd", &tkhd), mp4_box(b"mdia", &mdia)].concat(),
+        )
+    }
+
+    /// Build a minimal MP4 file with one `wvtt` track per `(language, samples)` entry.
+    pub fn build_mp4(tracks: &[(&str, Vec<Vec<u8>>)]) -> Vec<u8> {
+        let ftyp = mp4_box(b"ftyp", b"isom\0\0\0\0isom");
+        let mdat_body: Vec<u8> = tracks.iter().flat_map(|(_, s)| s.concat()).collect();
+        let mdat = mp4_box(b"mdat", &mdat_body);
+
+        let mut offset = (ftyp.len() + 8) as u32;
+        let mut traks = Vec::new();
+        for (index, (language, samples)) in tracks.iter().enumerate() {
+            traks.extend(trak(index as u32 + 1, language, b"wvtt", samples, offset));
+            offset += samples.iter().map(|s| s.len() as u32).sum::<u32>();
+        }
+
+        [ftyp, mdat, mp4_box(b"moov", &traks)].concat()
+    }
+
+    pub fn wvtt_samples(texts: &[&str]) -> Vec<Vec<u8>> {
+        texts
+            .iter()
+            .map(|text| {
+                if text.is_empty() {
+                    mp4_box(b"vtte", &[])
+                } else {
+                    vttc(text)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_read_wvtt_track() {
+        let data = build_mp4(&[(
+            "eng",
+            wvtt_samples(&["<v Alice>Hello there.", "", "<v Bob>Hi Alice!"]),
+        )]);
+
+        let tracks = read_tracks(&mut Cursor::new(data)).unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].id, 1);
+        assert_eq!(tracks[0].language.as_deref(), Some("eng"));
+        assert_eq!(tracks[0].name.as_deref(), Some("Captions"));
+
+        // The empty vtte sample is a gap and produces no cue
+        let cues = &tracks[0].cues;
+        assert_eq!(cues.len(), 2);
+        assert_eq!((cues[0].start_ms, cues[0].end_ms), (0, 2000));
+        assert_eq!(cues[0].payload, "<v Alice>Hello there.");
+        assert_eq!((cues[1].start_ms, cues[1].end_ms), (4000, 6000));
+        assert_eq!(cues[1].payload, "<v Bob>Hi Alice!");
+    }
+
+    #[test]
+    fn test_non_wvtt_tracks_are_skipped() {
+        let ftyp = mp4_box(b"ftyp", b"isom\0\0\0\0isom");
+        let moov = mp4_box(b"moov", &trak(1, "eng", b"tx3g", &[], 0));
+        let tracks = read_tracks(&mut Cursor::new([ftyp, moov].concat())).unwrap();
+        assert!(tracks.is_empty());
+    }
+
+    #[test]
+    fn test_missing_moov_is_parse_error() {
+        let data = mp4_box(b"ftyp", b"isom\0\0\0\0isom");
+        match read_tracks(&mut Cursor::new(data)) {
+            Err(VttError::ParseError { reason }) => assert!(reason.contains("moov")),
+            other => panic!("Expected ParseError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_oversized_sample_counts_are_malformed() {
+        let stbl = |stsz: &[u32], stts: &[u32]| {
+            [
+                full_box(b"stts", &u32s(stts)),
+                full_box(b"stsc", &u32s(&[1, 1, 1, 1])),
+                full_box(b"stsz", &u32s(stsz)),
+                full_box(b"stco", &u32s(&[1, 0])),
+            ]
+            .concat()
+        };
+
+        // A size table far longer than its box, and fixed sizes larger than the file
+        for stsz in [&[0, u32::MAX][..], &[64, u32::MAX]] {
+            match sample_table(&stbl(stsz, &[1, 1, 1]), 4096) {
+                Err(VttError::ParseError { reason }) => assert!(reason.contains("stsz")),
+                other => panic!("Expected ParseError, got {:?}", other),
+            }
+        }
+        // A single timing run may cover any number of samples without expanding it
+        let samples = sample_table(&stbl(&[16, 1], &[1, u32::MAX, 1000]), 4096).unwrap();
+        assert_eq!(samples.len(), 1);
+    }
+
+    #[test]
+    fn test_box_size_overflow_is_malformed() {
+        let mut data = mp4_box(b"ftyp", b"isom\0\0\0\0isom");
+        data.extend(u32s(&[1]));
+        data.extend(b"free");
+        data.extend(u64::MAX.to_be_bytes());
+        match read_tracks(&mut Cursor::new(data)) {
+            Err(VttError::ParseError { reason }) => assert!(reason.contains("free")),
+            other => panic!("Expected ParseError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_truncated_large_size_is_parse_error() {
+        let mut data = mp4_box(b"ftyp", b"isom\0\0\0\0isom");
+        data.extend(u32s(&[1]));
+        data.extend(b"moov");
+        data.extend([0, 0, 0]);
+        match read_tracks(&mut Cursor::new(data)) {
+            Err(VttError::ParseError { reason }) => {
+                assert_eq!(reason, "Truncated MP4 box 'moov'")
+            }
+            other => panic!("Expected ParseError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_is_iso_bmff() {
+        assert!(is_iso_bmff(b"\0\0\0\x18ftypisom"));
+        assert!(is_iso_bmff(b"\0\0\0\x08free"));
+        assert!(!is_iso_bmff(b"WEBVTT\n\n"));
+        assert!(!is_iso_bmff(b"\0\0"));
+    }
+
+    #[test]
+    fn test_media_header_language() {
+        let mut mdhd = u32s(&[0, 0, 0, 90000, 0]);
+        // "und" is treated as no language
+        mdhd.extend(((21u16 << 10) | (14 << 5) | 4).to_be_bytes());
+        assert_eq!(media_header(&mdhd), Some((90000, None)));
+    }
+}
@@ -0,0 +1,225 @@
+//! CSV and TSV tabular output.
+//!
+//! One row is written per record (consolidated speaker turn or individual cue), after a
+//! header row naming the columns. The columns default to start, end, duration,
+//! speaker, word count and text, and can be chosen and reordered with `--columns`.
+//!
+//! CSV follows RFC 4180: fields containing a comma, quote or line break are quoted,
+//! quotes are doubled, and rows end with CRLF. TSV has no quoting, so tabs and line
+//! breaks inside a field are replaced with spaces.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let csv = format_csv(Records::Segments(&segments), &[Column::Speaker, Column::Text]);
+//! // "speaker,text\r\nAlice,\"Hello, everyone.\"\r\n"
+//! ```
+
+use crate::cli::Column;
+use crate::json::Records;
+use crate::parser::unescape_markdown;
+use crate::timestamp::parse_timestamp;
+use clap::ValueEnum;
+
+/// The fields of one row, before column selection.
+struct Row<'a> {
+    start: Option<&'a str>,
+    end: Option<&'a str>,
+    speaker: Option<&'a str>,
+    text: &'a str,
+}
+
+impl Row<'_> {
+    /// The value of a column, unescaped and unquoted.
+    fn field(&self, column: Column) -> String {
+        match column {
+            Column::Start => self.start.unwrap_or_default().to_string(),
+            Column::End => self.end.unwrap_or_default().to_string(),
+            Column::Duration => self.duration().unwrap_or_default(),
+            Column::Speaker => self.speaker.map(unescape_markdown).unwrap_or_default(),
+            Column::Words => self.text.split_whitespace().count().to_string(),
+            Column::Text => self.text.to_string(),
+        }
+    }
+
+    /// Duration in seconds with millisecond precision, if both times are known.
+    fn duration(&self) -> Option<String> {
+        let start = parse_timestamp(self.start?)?;
+        let end = parse_timestamp(self.end?)?;
+        let millis = end.saturating_sub(start);
+        Some(format!("{}.{:03}", millis / 1000, millis % 1000))
+    }
+}
+
+/// Format records as CSV (RFC 4180).
+///
+/// # Arguments
+///
+/// * `records` - The segments or cues to write, one per row
+/// * `columns` - The columns to write, in order; empty means all columns
+///
+/// # Returns
+///
+/// A String with a header row and one row per record, each ending with CRLF.
+pub fn format_csv(records: Records, columns: &[Column]) -> String {
+    format_table(records, columns, ',', "\r\n", quote_csv)
+}
+
+/// Format records as tab-separated values.
+///
+/// # Arguments
+///
+/// * `records` - The segments or cues to write, one per row
+/// * `columns` - The columns to write, in order; empty means all columns
+///
+/// # Returns
+///
+/// A String with a header row and one row per record, each ending with a newline.
+pub fn format_tsv(records: Records, columns: &[Column]) -> String {
+    format_table(records, columns, '\t', "\n", clean_tsv)
+}
+
+/// Lay out the header and record rows with the given separators and field encoding.
+fn format_table(
+    records: Records,
+    columns: &[Column],
+    delimiter: char,
+    line_ending: &str,
+    encode: fn(&str) -> String,
+) -> String {
+    let columns = if columns.is_empty() {
+        Column::value_variants()
+    } else {
+        columns
+    };
+
+    let rows: Vec<Row> = match records {
+        Records::Segments(segments) => segments
+            .iter()
+            .map(|segment| Row {
+                start: segment.timestamps.first().map(String::as_str),
+                end: segment.end_timestamp.as_deref(),
+                speaker: Some(&segment.speaker),
+                text: &segment.text,
+            })
+            .collect(),
+        Records::Cues(cues) => cues
+            .iter()
+            .map(|cue| Row {
+                start: cue.timestamp.as_deref(),
+                end: cue.end_timestamp.as_deref(),
+                speaker: cue.speaker.as_deref(),
+                text: &cue.text,
+            })
+            .collect(),
+    };
+
+    let mut result = String::new();
+    let header: Vec<String> = columns
+        .iter()
+        .map(|column| column.header().to_string())
+        .collect();
+    result.push_str(&header.join(&delimiter.to_string()));
+    result.push_str(line_ending);
+
+    for row in &rows {
+        let fields: Vec<String> = columns
+            .iter()
+            .map(|&column| encode(&row.field(column)))
+            .collect();
+        result.push_str(&fields.join(&delimiter.to_string()));
+        result.push_str(line_ending);
+    }
+
+    result
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break.
+fn quote_csv(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Replace tabs and line breaks, which TSV cannot represent inside a field.
+fn clean_tsv(field: &str) -> String {
+    field.replace("\r\n", " ").replace(['\t', '\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consolidator::SpeakerSegment;
+    use crate::parser::Cue;
+
+    fn sample_segments() -> Vec<SpeakerSegment> {
+        vec![
+            SpeakerSegment {
+                speaker: "John\\*Doe".to_string(),
+                text: "Hello, \"everyone\".".to_string(),
+                timestamp: None,
+                timestamps: vec!["00:00:01.000".to_string(), "00:00:02.000".to_string()],
+                end_timestamp: Some("00:00:03.500".to_string()),
+                cues: Vec::new(),
+            },
+            SpeakerSegment {
+                speaker: "Bob".to_string(),
+                text: "Hi there".to_string(),
+                timestamp: None,
+                timestamps: vec!["00:01:00.000".to_string()],
+                end_timestamp: None,
+                cues: Vec::new(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_format_csv_segments() {
+        let segments = sample_segments();
+        assert_eq!(
+            format_csv(Records::Segments(&segments), &[]),
+            "start,end,duration,speaker,words,text\r\n\
+             00:00:01.000,00:00:03.500,2.500,John*Doe,2,\"Hello, \"\"everyone\"\".\"\r\n\
+             00:01:00.000,,,Bob,2,Hi there\r\n"
+        );
+    }
+
+    #[test]
+    fn test_format_csv_selected_columns() {
+        let segments = sample_segments();
+        assert_eq!(
+            format_csv(
+                Records::Segments(&segments),
+                &[Column::Speaker, Column::Words]
+            ),
+            "speaker,words\r\nJohn*Doe,2\r\nBob,2\r\n"
+        );
+    }
+
+    #[test]
+    fn test_format_tsv_cues() {
+        let cues = vec![Cue {
+            timestamp: Some("00:00:01.000".to_string()),
+            end_timestamp: Some("00:00:02.250".to_string()),
+            speaker: None,
+            text: "Line one\nline\ttwo".to_string(),
+            id: None,
+            settings: None,
+        }];
+        assert_eq!(
+            format_tsv(Records::Cues(&cues), &[]),
+            "start\tend\tduration\tspeaker\twords\ttext\n\
+             00:00:01.000\t00:00:02.250\t1.250\t\t4\tLine one line two\n"
+        );
+    }
+
+    #[test]
+    fn test_quote_csv() {
+        assert_eq!(quote_csv("plain"), "plain");
+        assert_eq!(quote_csv("a,b"), "\"a,b\"");
+        assert_eq!(quote_csv("two\nlines"), "\"two\nlines\"");
+        assert_eq!(quote_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
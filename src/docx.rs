@@ -0,0 +1,400 @@
+//! Word (`.docx`) transcript generation.
+//!
+//! This module writes a minimal Office Open XML package directly, with no dependency on
+//! pandoc or Word itself. The document has a title from the metadata, a participant
+//! table, and one paragraph per speaker turn laid out like the Markdown output: an
+//! optional timestamp, the speaker name in bold, then the consolidated text.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let bytes = format_docx(
+//!     &segments,
+//!     &metadata,
+//!     TimestampMode::First,
+//!     &TimestampFormat::default(),
+//! )?;
+//! std::fs::write("meeting.docx", bytes)?;
+//! ```
+
+use crate::cli::TimestampMode;
+use crate::consolidator::{Participant, SpeakerSegment, participants};
+use crate::error::VttError;
+use crate::parser::{DocumentMetadata, unescape_markdown};
+use crate::timestamp::TimestampFormat;
+use std::io::{self, Cursor, Write};
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+<Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>
+<Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
+</Types>"#;
+
+const PACKAGE_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>
+</Relationships>"#;
+
+const DOCUMENT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>
+</Relationships>"#;
+
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:docDefaults>
+<w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:hAnsi="Calibri" w:eastAsia="Calibri" w:cs="Calibri"/><w:sz w:val="22"/></w:rPr></w:rPrDefault>
+<w:pPrDefault><w:pPr><w:spacing w:after="160" w:line="259" w:lineRule="auto"/></w:pPr></w:pPrDefault>
+</w:docDefaults>
+<w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/><w:qFormat/></w:style>
+<w:style w:type="paragraph" w:styleId="Title"><w:name w:val="Title"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:spacing w:after="240"/></w:pPr><w:rPr><w:sz w:val="48"/></w:rPr></w:style>
+<w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="120"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:sz w:val="32"/></w:rPr></w:style>
+<w:style w:type="character" w:styleId="Timestamp"><w:name w:val="Timestamp"/><w:rPr><w:color w:val="666666"/></w:rPr></w:style>
+<w:style w:type="table" w:styleId="TableGrid"><w:name w:val="Table Grid"/><w:tblPr><w:tblBorders><w:top w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:left w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:bottom w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:right w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:insideH w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:insideV w:val="single" w:sz="4" w:space="0" w:color="auto"/></w:tblBorders></w:tblPr></w:style>
+</w:styles>"#;
+
+/// Format speaker segments as a Word document.
+///
+/// # Arguments
+///
+/// * `segments` - The consolidated speaker segments to format
+/// * `metadata` - Document metadata, used for the title
+/// * `timestamp_mode` - How to include timestamps (None, First, or Each)
+/// * `timestamp_format` - How to display timestamps
+///
+/// # Returns
+///
+/// The bytes of the `.docx` package, or `VttError::IoError` if the package could not
+/// be assembled.
+pub fn format_docx(
+    segments: &[SpeakerSegment],
+    metadata: &DocumentMetadata,
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+) -> Result<Vec<u8>, VttError> {
+    let title = metadata.display_title();
+    let parts = [
+        ("[Content_Types].xml", CONTENT_TYPES.to_string()),
+        ("_rels/.rels", PACKAGE_RELS.to_string()),
+        ("docProps/core.xml", core_properties(&title)),
+        ("word/_rels/document.xml.rels", DOCUMENT_RELS.to_string()),
+        ("word/styles.xml", STYLES.to_string()),
+        (
+            "word/document.xml",
+            document_xml(segments, &title, timestamp_mode, timestamp_format),
+        ),
+    ];
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    for (name, content) in parts {
+        writer.start_file(name, options).map_err(io::Error::from)?;
+        writer.write_all(content.as_bytes())?;
+    }
+    let cursor = writer.finish().map_err(io::Error::from)?;
+
+    Ok(cursor.into_inner())
+}
+
+/// Build `docProps/core.xml`, which carries the document title.
+fn core_properties(title: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/">
+<dc:title>{}</dc:title>
+</cp:coreProperties>"#,
+        escape_xml(title)
+    )
+}
+
+/// Build `word/document.xml`: title, participant table, and transcript paragraphs.
+fn document_xml(
+    segments: &[SpeakerSegment],
+    title: &str,
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+) -> String {
+    let mut body = String::new();
+    body.push_str(&paragraph(Some("Title"), &run(title, None)));
+
+    body.push_str(&paragraph(Some("Heading1"), &run("Participants", None)));
+    body.push_str(&participant_table(&participants(segments)));
+
+    body.push_str(&paragraph(Some("Heading1"), &run("Transcript", None)));
+    let labels = timestamp_format.turn_labels(segments, timestamp_mode);
+    for (segment, label) in segments.iter().zip(&labels) {
+        let mut runs = String::new();
+        if let Some(label) = label {
+            runs.push_str(&run(
+                &format!("[{}] ", label),
+                Some("<w:rStyle w:val=\"Timestamp\"/>"),
+            ));
+        }
+        runs.push_str(&run(
+            &format!("{}:", unescape_markdown(&segment.speaker)),
+            Some("<w:b/>"),
+        ));
+        // Each paragraph of the turn is its own `<w:p>`; the speaker leads the first
+        let texts = timestamp_format.paragraph_texts(segment, timestamp_mode, str::to_string);
+        let mut texts = texts.iter();
+        if let Some(first) = texts.next() {
+            runs.push_str(&run(&format!(" {}", first), None));
+        }
+        body.push_str(&paragraph(None, &runs));
+        for text in texts {
+            body.push_str(&paragraph(None, &run(text, None)));
+        }
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}<w:sectPr><w:pgSz w:w="12240" w:h="15840"/><w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440" w:header="720" w:footer="720" w:gutter="0"/></w:sectPr></w:body></w:document>"#,
+        body
+    )
+}
+
+/// Build the participant table with a bold header row.
+fn participant_table(participants: &[Participant]) -> String {
+    let mut table = String::from(
+        "<w:tbl><w:tblPr><w:tblStyle w:val=\"TableGrid\"/><w:tblW w:w=\"0\" w:type=\"auto\"/></w:tblPr>",
+    );
+    table.push_str("<w:tblGrid><w:gridCol w:w=\"4680\"/><w:gridCol w:w=\"2340\"/><w:gridCol w:w=\"2340\"/></w:tblGrid>");
+
+    let header = ["Participant", "Turns", "Words"];
+    table.push_str(&table_row(&header, Some("<w:b/>")));
+    for participant in participants {
+        let cells = [
+            unescape_markdown(participant.speaker),
+            participant.turns.to_string(),
+            participant.words.to_string(),
+        ];
+        table.push_str(&table_row(&cells, None));
+    }
+
+    table.push_str("</w:tbl>");
+    table
+}
+
+fn table_row<S: AsRef<str>>(cells: &[S], properties: Option<&str>) -> String {
+    let mut row = String::from("<w:tr>");
+    for cell in cells {
+        row.push_str("<w:tc>");
+        row.push_str(&paragraph(None, &run(cell.as_ref(), properties)));
+        row.push_str("</w:tc>");
+    }
+    row.push_str("</w:tr>");
+    row
+}
+
+fn paragraph(style: Option<&str>, runs: &str) -> String {
+    match style {
+        Some(style) => format!(
+            "<w:p><w:pPr><w:pStyle w:val=\"{}\"/></w:pPr>{}</w:p>",
+            style, runs
+        ),
+        None => format!("<w:p>{}</w:p>", runs),
+    }
+}
+
+fn run(text: &str, properties: Option<&str>) -> String {
+    let properties = properties
+        .map(|properties| format!("<w:rPr>{}</w:rPr>", properties))
+        .unwrap_or_default();
+    format!(
+        "<w:r>{}<w:t xml:space=\"preserve\">{}</w:t></w:r>",
+        properties,
+        escape_xml(text)
+    )
+}
+
+/// Escape text for XML, dropping control characters that XML 1.0 does not allow.
+fn escape_xml(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        match ch {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            '\t' | '\n' | '\r' => result.push(ch),
+            c if c.is_control() => {}
+            _ => result.push(ch),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consolidator::SegmentCue;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    fn segment(speaker: &str, text: &str, timestamp: &str) -> SpeakerSegment {
+        SpeakerSegment {
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+            timestamp: Some(timestamp.to_string()),
+            timestamps: vec![timestamp.to_string()],
+            end_timestamp: None,
+            cues: Vec::new(),
+        }
+    }
+
+    fn read_part(bytes: &[u8], name: &str) -> String {
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("A & B <c>"), "A &amp; B &lt;c&gt;");
+        assert_eq!(escape_xml("bell\u{7}"), "bell");
+    }
+
+    #[test]
+    fn test_format_docx_package() {
+        let segments = vec![
+            segment("Alice", "Hello everyone.", "00:00:01.000"),
+            segment("John\\*Doe", "Tom & Jerry.", "00:00:05.000"),
+            segment("Alice", "Let's begin now.", "00:00:09.000"),
+        ];
+        let metadata = DocumentMetadata {
+            title: Some("Weekly sync".to_string()),
+            ..DocumentMetadata::default()
+        };
+
+        let bytes = format_docx(
+            &segments,
+            &metadata,
+            TimestampMode::First,
+            &TimestampFormat::default(),
+        )
+        .unwrap();
+        let archive = ZipArchive::new(Cursor::new(bytes.as_slice())).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        for part in [
+            "[Content_Types].xml",
+            "_rels/.rels",
+            "word/document.xml",
+            "word/styles.xml",
+        ] {
+            assert!(names.contains(&part), "missing {}", part);
+        }
+
+        let document = read_part(&bytes, "word/document.xml");
+        assert!(document.contains(
+            "<w:pStyle w:val=\"Title\"/></w:pPr><w:r><w:t xml:space=\"preserve\">Weekly sync</w:t>"
+        ));
+        assert!(document.contains(
+            "<w:r><w:rPr><w:rStyle w:val=\"Timestamp\"/></w:rPr><w:t xml:space=\"preserve\">[00:00:01.000] </w:t></w:r>\
+             <w:r><w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">Alice:</w:t></w:r>\
+             <w:r><w:t xml:space=\"preserve\"> Hello everyone.</w:t></w:r>"
+        ));
+        assert!(document.contains("Tom &amp; Jerry."));
+
+        // Participant table: Alice has two turns and five words
+        assert!(document.contains("<w:tblStyle w:val=\"TableGrid\"/>"));
+        assert!(document.contains(
+            "<w:t xml:space=\"preserve\">Alice</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t xml:space=\"preserve\">2</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t xml:space=\"preserve\">5</w:t>"
+        ));
+        assert!(document.contains(">John*Doe</w:t>"));
+
+        assert!(
+            read_part(&bytes, "docProps/core.xml").contains("<dc:title>Weekly sync</dc:title>")
+        );
+    }
+
+    #[test]
+    fn test_format_docx_inline_cue_timestamps() {
+        let cue = |timestamp: &str, text: &str| SegmentCue {
+            timestamp: Some(timestamp.to_string()),
+            end_timestamp: None,
+            text: text.to_string(),
+            new_paragraph: false,
+        };
+        let segments = vec![SpeakerSegment {
+            cues: vec![
+                cue("00:00:01.000", "Hello <all>."),
+                cue("00:00:02.000", "How are you?"),
+            ],
+            ..segment("Alice", "Hello <all>. How are you?", "00:00:01.000")
+        }];
+        let bytes = format_docx(
+            &segments,
+            &DocumentMetadata::default(),
+            TimestampMode::Each,
+            &TimestampFormat::default(),
+        )
+        .unwrap();
+
+        let document = read_part(&bytes, "word/document.xml");
+        assert!(document.contains(
+            "<w:t xml:space=\"preserve\"> Hello &lt;all&gt;. [00:00:02.000] How are you?</w:t>"
+        ));
+    }
+
+    #[test]
+    fn test_format_docx_paragraphs() {
+        let cue = |timestamp: &str, text: &str, new_paragraph: bool| SegmentCue {
+            timestamp: Some(timestamp.to_string()),
+            end_timestamp: None,
+            text: text.to_string(),
+            new_paragraph,
+        };
+        let segments = vec![SpeakerSegment {
+            cues: vec![
+                cue("00:00:01.000", "First point.", false),
+                cue("00:00:09.000", "Second point.", true),
+            ],
+            ..segment("Alice", "First point.\n\nSecond point.", "00:00:01.000")
+        }];
+        let bytes = format_docx(
+            &segments,
+            &DocumentMetadata::default(),
+            TimestampMode::None,
+            &TimestampFormat::default(),
+        )
+        .unwrap();
+
+        let document = read_part(&bytes, "word/document.xml");
+        assert!(document.contains(
+            "<w:p><w:r><w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">Alice:</w:t></w:r>\
+             <w:r><w:t xml:space=\"preserve\"> First point.</w:t></w:r></w:p>\
+             <w:p><w:r><w:t xml:space=\"preserve\">Second point.</w:t></w:r></w:p>"
+        ));
+        assert!(!document.contains("<w:br/>"));
+    }
+
+    #[test]
+    fn test_format_docx_without_timestamps() {
+        let segments = vec![segment("Alice", "Hello.", "00:00:01.000")];
+        let bytes = format_docx(
+            &segments,
+            &DocumentMetadata::default(),
+            TimestampMode::None,
+            &TimestampFormat::default(),
+        )
+        .unwrap();
+
+        let document = read_part(&bytes, "word/document.xml");
+        assert!(!document.contains("[00:00:01.000]"));
+        assert!(document.contains(">Transcript</w:t>"));
+    }
+}
@@ -6,7 +6,7 @@
 //! # Example
 //!
 //! ```rust,ignore
-//! use std::path::PathBuf;
+//! use std::path::{Path, PathBuf};
 //! use vtt_to_md::error::VttError;
 //!
 //! let error = VttError::FileNotFound {
@@ -17,7 +17,7 @@
 //! ```
 
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use thiserror::Error;
 
@@ -57,17 +57,49 @@ pub enum VttError {
 
     /// Invalid command-line usage.
     #[error("Invalid usage: {reason}")]
-    #[allow(dead_code)] // Reserved for future usage validation
     UsageError { reason: String },
+
+    /// Output template failed to compile or render.
+    #[error(
+        "Template error in {}{}: {reason}",
+        .path.display(),
+        .line.map(|line| format!(" at line {}", line)).unwrap_or_default()
+    )]
+    TemplateError {
+        path: PathBuf,
+        line: Option<usize>,
+        reason: String,
+    },
+
+    /// Calendar invite could not be used.
+    #[error("Failed to read calendar invite {}: {reason}", .path.display())]
+    CalendarError { path: PathBuf, reason: String },
 }
 
 impl VttError {
+    /// Build an error for a failed read of an input file.
+    ///
+    /// Maps `NotFound` and `PermissionDenied` to their dedicated variants so they
+    /// get the right exit codes; everything else becomes a general I/O error.
+    pub fn from_read_error(path: &Path, error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => VttError::FileNotFound {
+                path: path.to_path_buf(),
+            },
+            io::ErrorKind::PermissionDenied => VttError::PermissionDenied {
+                path: path.to_path_buf(),
+            },
+            _ => VttError::IoError(error),
+        }
+    }
+
     /// Map error to appropriate BSD sysexits.h exit code.
     ///
     /// # Exit Code Mapping
     ///
     /// - `64` (EX_USAGE): Invalid command-line usage or conflicting arguments
-    /// - `65` (EX_DATAERR): Invalid VTT file format or parse errors
+    /// - `65` (EX_DATAERR): Invalid VTT file format, parse errors, template errors, or
+    ///   unusable calendar invites
     /// - `66` (EX_NOINPUT): Input file not found
     /// - `73` (EX_CANTCREAT): Output file already exists without --force
     /// - `74` (EX_IOERR): General I/O or write errors
@@ -76,6 +108,8 @@ impl VttError {
         let code = match self {
             VttError::UsageError { .. } => 64,       // EX_USAGE
             VttError::ParseError { .. } => 65,       // EX_DATAERR
+            VttError::TemplateError { .. } => 65,    // EX_DATAERR
+            VttError::CalendarError { .. } => 65,    // EX_DATAERR
             VttError::FileNotFound { .. } => 66,     // EX_NOINPUT
             VttError::OutputExists { .. } => 73,     // EX_CANTCREAT
             VttError::WriteError { .. } => 74,       // EX_IOERR
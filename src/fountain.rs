@@ -0,0 +1,151 @@
+//! Fountain screenplay generation.
+//!
+//! [Fountain](https://fountain.io) is a plain-text screenplay format. Each speaker turn
+//! becomes a dialogue block: the speaker's name in uppercase as the character cue,
+//! an optional timestamp as a parenthetical, and the consolidated text as dialogue.
+//! The document title goes on the title page.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let script = format_fountain(
+//!     &segments,
+//!     &metadata,
+//!     TimestampMode::None,
+//!     &TimestampFormat::default(),
+//! );
+//! // "Title: Episode 12\n\nALICE\nWelcome to the show.\n\n"
+//! ```
+
+use crate::cli::TimestampMode;
+use crate::consolidator::SpeakerSegment;
+use crate::parser::{DocumentMetadata, unescape_markdown};
+use crate::timestamp::TimestampFormat;
+
+/// Format speaker segments as a Fountain screenplay.
+///
+/// # Arguments
+///
+/// * `segments` - The consolidated speaker segments to format
+/// * `metadata` - Document metadata, used for the title page
+/// * `timestamp_mode` - How to include timestamps (None, First, or Each)
+/// * `timestamp_format` - How to display timestamps
+///
+/// # Returns
+///
+/// A String containing the Fountain document.
+pub fn format_fountain(
+    segments: &[SpeakerSegment],
+    metadata: &DocumentMetadata,
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+) -> String {
+    let mut result = format!("Title: {}\n\n", metadata.display_title());
+
+    let labels = timestamp_format.turn_labels(segments, timestamp_mode);
+    for (segment, label) in segments.iter().zip(&labels) {
+        result.push_str(&character_cue(&unescape_markdown(&segment.speaker)));
+        result.push('\n');
+        if let Some(label) = label {
+            result.push_str(&format!("({})\n", label));
+        }
+        result.push_str(&timestamp_format.inline_text(
+            segment,
+            timestamp_mode,
+            escape_fountain,
+            // A line with two spaces keeps the dialogue going past a blank line
+            "\n  \n",
+        ));
+        result.push_str("\n\n");
+    }
+
+    result
+}
+
+/// Build the character cue line for a speaker.
+///
+/// Fountain only recognizes an all-caps line containing a letter as a character cue,
+/// so names without letters, or starting with a symbol that forces another element
+/// type, are forced with a leading `@`.
+fn character_cue(speaker: &str) -> String {
+    let name = speaker.to_uppercase();
+    if name.chars().any(char::is_alphabetic)
+        && !name.starts_with(['!', '@', '~', '.', '>', '#', '='])
+    {
+        name
+    } else {
+        format!("@{}", name)
+    }
+}
+
+/// Escape Fountain emphasis markers so dialogue is printed as spoken.
+fn escape_fountain(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        if matches!(ch, '*' | '_' | '\\') {
+            result.push('\\');
+        }
+        result.push(ch);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(speaker: &str, text: &str, timestamp: &str) -> SpeakerSegment {
+        SpeakerSegment {
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+            timestamp: Some(timestamp.to_string()),
+            timestamps: vec![timestamp.to_string()],
+            end_timestamp: None,
+            cues: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_format_fountain() {
+        let segments = vec![
+            segment("Alice", "Welcome to the show.", "00:00:01.000"),
+            segment("Dr. O'Brien", "Glad to *finally* be here.", "00:00:04.000"),
+        ];
+        let metadata = DocumentMetadata {
+            title: Some("Episode 12".to_string()),
+            ..DocumentMetadata::default()
+        };
+
+        assert_eq!(
+            format_fountain(
+                &segments,
+                &metadata,
+                TimestampMode::None,
+                &TimestampFormat::default()
+            ),
+            "Title: Episode 12\n\n\
+             ALICE\nWelcome to the show.\n\n\
+             DR. O'BRIEN\nGlad to \\*finally\\* be here.\n\n"
+        );
+
+        assert_eq!(
+            format_fountain(
+                &segments[..1],
+                &metadata,
+                TimestampMode::First,
+                &TimestampFormat::default()
+            ),
+            "Title: Episode 12\n\nALICE\n(00:00:01.000)\nWelcome to the show.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_character_cue() {
+        assert_eq!(character_cue("Alice"), "ALICE");
+        assert_eq!(character_cue("José"), "JOSÉ");
+        assert_eq!(character_cue("42"), "@42");
+        assert_eq!(character_cue("#host"), "@#HOST");
+    }
+}
@@ -0,0 +1,293 @@
+//! YAML front matter for Markdown output.
+//!
+//! Obsidian vaults and static-site generators read document properties from a YAML
+//! block at the top of a Markdown file. With `--front-matter`, the Markdown output
+//! starts with such a block describing the meeting:
+//!
+//! ```yaml
+//! ---
+//! title: "Weekly sync"
+//! date: 2025-11-18
+//! duration: "00:42:10"
+//! participants:
+//!   - "Alice"
+//!   - "Bob"
+//! cues: 412
+//! words: 5234
+//! source: "weekly-sync.vtt"
+//! source_sha256: "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+//! ---
+//! ```
+//!
+//! The date is the meeting's start date from the calendar invite (`--ics`), or else
+//! the input file's modification date (UTC), since captions don't record when the
+//! meeting took place. The duration runs from the first cue's start to the
+//! last cue's end.
+
+use crate::consolidator::participants;
+use crate::error::VttError;
+use crate::output::Transcript;
+use crate::parser::unescape_markdown;
+use crate::timestamp::parse_timestamp;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Metadata written to the front matter block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrontMatter {
+    /// Document title
+    pub title: String,
+    /// Meeting date, or modification date of the input file, as `YYYY-MM-DD`
+    pub date: Option<String>,
+    /// Time from the first cue's start to the last cue's end, as `HH:MM:SS`
+    pub duration: Option<String>,
+    /// Speaker names in order of first appearance
+    pub participants: Vec<String>,
+    /// Number of cues in the transcript
+    pub cues: usize,
+    /// Number of words spoken
+    pub words: usize,
+    /// File name of the input
+    pub source: Option<String>,
+    /// SHA-256 of the input file, in lowercase hex
+    pub source_sha256: String,
+}
+
+impl FrontMatter {
+    /// Collect front matter for a transcript and the input file it came from.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Path of the input file, for its date, name and hash
+    /// * `transcript` - The converted transcript
+    ///
+    /// # Returns
+    ///
+    /// The front matter, or an error if the input file can't be read for hashing.
+    pub fn collect(input: &Path, transcript: &Transcript) -> Result<FrontMatter, VttError> {
+        let participants = participants(transcript.segments);
+        let words = participants
+            .iter()
+            .map(|participant| participant.words)
+            .sum();
+
+        let start = transcript
+            .cues
+            .iter()
+            .filter_map(|cue| cue.timestamp.as_deref().and_then(parse_timestamp))
+            .min();
+        let end = transcript
+            .cues
+            .iter()
+            .filter_map(|cue| cue.end_timestamp.as_deref().and_then(parse_timestamp))
+            .max();
+        let duration = match (start, end) {
+            (Some(start), Some(end)) => Some(format_duration(end.saturating_sub(start))),
+            _ => None,
+        };
+
+        // The meeting's own date when a calendar invite was given
+        let meeting_date = transcript
+            .metadata
+            .meeting
+            .as_ref()
+            .and_then(|meeting| meeting.start.as_deref())
+            .and_then(|start| start.get(..10))
+            .map(str::to_string);
+        let date = meeting_date.or_else(|| {
+            fs::metadata(input)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map(|since_epoch| civil_date(since_epoch.as_secs() / 86_400))
+        });
+
+        Ok(FrontMatter {
+            title: transcript.metadata.display_title(),
+            date,
+            duration,
+            participants: participants
+                .iter()
+                .map(|participant| unescape_markdown(participant.speaker))
+                .collect(),
+            cues: transcript.cues.len(),
+            words,
+            source: input
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned()),
+            source_sha256: file_sha256(input)?,
+        })
+    }
+
+    /// Render the front matter as a YAML block delimited by `---` lines, followed by
+    /// a blank line.
+    pub fn to_yaml(&self) -> String {
+        let mut result = String::from("---\n");
+
+        result.push_str(&format!("title: {}\n", yaml_string(&self.title)));
+        if let Some(ref date) = self.date {
+            result.push_str(&format!("date: {}\n", date));
+        }
+        if let Some(ref duration) = self.duration {
+            // Quoted, since YAML 1.1 reads unquoted HH:MM:SS as a base-60 number
+            result.push_str(&format!("duration: {}\n", yaml_string(duration)));
+        }
+        if self.participants.is_empty() {
+            result.push_str("participants: []\n");
+        } else {
+            result.push_str("participants:\n");
+            for participant in &self.participants {
+                result.push_str(&format!("  - {}\n", yaml_string(participant)));
+            }
+        }
+        result.push_str(&format!("cues: {}\n", self.cues));
+        result.push_str(&format!("words: {}\n", self.words));
+        if let Some(ref source) = self.source {
+            result.push_str(&format!("source: {}\n", yaml_string(source)));
+        }
+        result.push_str(&format!(
+            "source_sha256: {}\n",
+            yaml_string(&self.source_sha256)
+        ));
+
+        result.push_str("---\n\n");
+        result
+    }
+}
+
+/// Hash a file with SHA-256 without reading it into memory at once.
+fn file_sha256(path: &Path) -> Result<String, VttError> {
+    let mut file = File::open(path).map_err(|e| VttError::from_read_error(path, e))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Format milliseconds as `HH:MM:SS`, dropping the milliseconds.
+fn format_duration(millis: u64) -> String {
+    let seconds = millis / 1000;
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
+
+/// Convert days since 1970-01-01 to a `YYYY-MM-DD` date in the proleptic Gregorian
+/// calendar.
+fn civil_date(days: u64) -> String {
+    // Shift the epoch to 0000-03-01 so leap days fall at the end of each year
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Write a string as a double-quoted YAML scalar.
+fn yaml_string(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+    result.push('"');
+
+    for ch in text.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_yaml() {
+        let front_matter = FrontMatter {
+            title: "Sync: \"Q3\"".to_string(),
+            date: Some("2025-11-18".to_string()),
+            duration: Some("00:42:10".to_string()),
+            participants: vec!["Alice".to_string(), "John*Doe".to_string()],
+            cues: 12,
+            words: 345,
+            source: Some("sync.vtt".to_string()),
+            source_sha256: "ab12".to_string(),
+        };
+
+        assert_eq!(
+            front_matter.to_yaml(),
+            "---\n\
+             title: \"Sync: \\\"Q3\\\"\"\n\
+             date: 2025-11-18\n\
+             duration: \"00:42:10\"\n\
+             participants:\n  - \"Alice\"\n  - \"John*Doe\"\n\
+             cues: 12\n\
+             words: 345\n\
+             source: \"sync.vtt\"\n\
+             source_sha256: \"ab12\"\n\
+             ---\n\n"
+        );
+
+        let empty = FrontMatter {
+            participants: vec![],
+            date: None,
+            duration: None,
+            ..front_matter
+        };
+        assert!(empty.to_yaml().contains("participants: []\ncues: 12\n"));
+    }
+
+    #[test]
+    fn test_civil_date() {
+        assert_eq!(civil_date(0), "1970-01-01");
+        assert_eq!(civil_date(11_016), "2000-02-29");
+        assert_eq!(civil_date(20_410), "2025-11-18");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "00:00:00");
+        assert_eq!(format_duration(2_530_999), "00:42:10");
+        assert_eq!(format_duration(36_000_000), "10:00:00");
+    }
+
+    #[test]
+    fn test_file_sha256() {
+        let temp_file = std::env::temp_dir().join("test_front_matter_hash.vtt");
+        fs::write(&temp_file, "test").unwrap();
+
+        assert_eq!(
+            file_sha256(&temp_file).unwrap(),
+            "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+        );
+
+        fs::remove_file(&temp_file).ok();
+    }
+}
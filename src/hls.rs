@@ -0,0 +1,278 @@
+//! HLS segmented WebVTT playlist input.
+//!
+//! Streaming stacks store captions as an `.m3u8` media playlist referencing many short
+//! `.vtt` segments. Each segment is a standalone WebVTT file whose cue times are mapped
+//! onto the stream timeline by an `X-TIMESTAMP-MAP` header. This module reads a local
+//! playlist, parses every segment with [`VttDocument::parse_str`], shifts each segment's
+//! cues by its offset, and drops the duplicated cues that segmenters repeat on both sides
+//! of a segment boundary.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use vtt_to_md::hls::parse_playlist;
+//!
+//! let doc = parse_playlist("captions/index.m3u8")?;
+//! println!("{} cues", doc.cues.len());
+//! ```
+
+use crate::error::VttError;
+use crate::parser::{Cue, DocumentMetadata, VttDocument};
+use crate::timestamp::{format_timestamp, parse_timestamp};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// MPEG-TS timestamps use a 90 kHz clock.
+const MPEGTS_TICKS_PER_MS: i64 = 90;
+
+/// MPEG-TS timestamps are 33-bit values that wrap around.
+const MPEGTS_ROLLOVER: i64 = 1 << 33;
+
+/// Parse a local HLS playlist of WebVTT segments into a single document.
+///
+/// Segment URIs are resolved relative to the playlist's directory. Cues from every
+/// segment are shifted onto a common timeline that starts at the first segment's
+/// `X-TIMESTAMP-MAP` anchor, de-duplicated, and sorted by start time.
+///
+/// # Returns
+///
+/// Returns `Ok(VttDocument)` if parsing succeeds, or `Err(VttError)` if:
+/// - The playlist or a segment cannot be read
+/// - The playlist is missing the `#EXTM3U` header or is a master playlist
+/// - A segment references a remote URL
+/// - A segment is not valid WebVTT
+pub fn parse_playlist<P: AsRef<Path>>(path: P) -> Result<VttDocument, VttError> {
+    let path = path.as_ref();
+    let playlist = fs::read_to_string(path).map_err(|e| VttError::from_read_error(path, e))?;
+    let uris = segment_uris(&playlist)?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut cues = Vec::new();
+    let mut seen = HashSet::new();
+    let mut has_voice_tags = false;
+    let mut first_anchor: Option<i64> = None;
+    let mut previous_mpegts: Option<i64> = None;
+    let mut rollover = 0;
+
+    for uri in uris {
+        let segment_path = base_dir.join(&uri);
+        let content = fs::read_to_string(&segment_path)
+            .map_err(|e| VttError::from_read_error(&segment_path, e))?;
+        let document = VttDocument::parse_str(&content).map_err(|e| match e {
+            VttError::ParseError { reason } => VttError::ParseError {
+                reason: format!("segment {}: {}", uri, reason),
+            },
+            other => other,
+        })?;
+        has_voice_tags |= document.has_voice_tags;
+
+        // Compute this segment's position on the stream timeline in milliseconds
+        let anchor = match parse_timestamp_map(&content) {
+            Some((mpegts, local_ms)) => {
+                // Account for the 33-bit MPEG-TS counter wrapping between segments
+                if let Some(previous) = previous_mpegts
+                    && mpegts + rollover < previous - MPEGTS_ROLLOVER / 2
+                {
+                    rollover += MPEGTS_ROLLOVER;
+                }
+                let mpegts = mpegts + rollover;
+                previous_mpegts = Some(mpegts);
+                mpegts / MPEGTS_TICKS_PER_MS - local_ms
+            }
+            None => 0,
+        };
+        let offset = anchor - *first_anchor.get_or_insert(anchor);
+
+        for cue in document.cues {
+            let cue = shift_cue(cue, offset);
+            let key = (
+                cue.timestamp.clone(),
+                cue.end_timestamp.clone(),
+                cue.speaker.clone(),
+                cue.text.clone(),
+            );
+            if seen.insert(key) {
+                cues.push(cue);
+            }
+        }
+    }
+
+    // Segments are usually in order, but sort to produce one continuous timeline
+    cues.sort_by_key(|cue| {
+        cue.timestamp
+            .as_deref()
+            .and_then(parse_timestamp)
+            .unwrap_or(u64::MAX)
+    });
+
+    Ok(VttDocument {
+        cues,
+        has_voice_tags,
+        metadata: DocumentMetadata::default(),
+    })
+}
+
+/// Extract segment URIs from a media playlist.
+fn segment_uris(playlist: &str) -> Result<Vec<String>, VttError> {
+    let mut lines = playlist
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+
+    if lines.next().map(|line| line.trim_start_matches('\u{feff}')) != Some("#EXTM3U") {
+        return Err(VttError::ParseError {
+            reason: "Missing #EXTM3U playlist header".to_string(),
+        });
+    }
+
+    let mut uris = Vec::new();
+    for line in lines {
+        if line.starts_with("#EXT-X-STREAM-INF") || line.starts_with("#EXT-X-MEDIA:") {
+            return Err(VttError::ParseError {
+                reason: "Master playlists are not supported; use the subtitle media playlist"
+                    .to_string(),
+            });
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.contains("://") {
+            return Err(VttError::ParseError {
+                reason: format!("Remote playlist segments are not supported: {}", line),
+            });
+        }
+        uris.push(line.to_string());
+    }
+
+    Ok(uris)
+}
+
+/// Parse the `X-TIMESTAMP-MAP=MPEGTS:<ticks>,LOCAL:<timestamp>` header of a segment.
+///
+/// Returns the MPEG-TS value in 90 kHz ticks and the local cue time in milliseconds.
+/// Only the header block (up to the first blank line) is searched.
+fn parse_timestamp_map(content: &str) -> Option<(i64, i64)> {
+    let line = content
+        .lines()
+        .take_while(|line| !line.trim().is_empty())
+        .find_map(|line| line.trim().strip_prefix("X-TIMESTAMP-MAP="))?;
+
+    let mut mpegts = None;
+    let mut local = None;
+    for field in line.split(',') {
+        if let Some(value) = field.trim().strip_prefix("MPEGTS:") {
+            mpegts = value.trim().parse::<i64>().ok();
+        } else if let Some(value) = field.trim().strip_prefix("LOCAL:") {
+            local = parse_timestamp(value).map(|ms| ms as i64);
+        }
+    }
+
+    Some((mpegts?, local.unwrap_or(0)))
+}
+
+/// Shift a cue's start and end timestamps by `offset` milliseconds, clamping at zero.
+fn shift_cue(mut cue: Cue, offset: i64) -> Cue {
+    let shift = |timestamp: Option<String>| {
+        timestamp.map(|ts| match parse_timestamp(&ts) {
+            Some(ms) => format_timestamp((ms as i64 + offset).max(0) as u64),
+            None => ts,
+        })
+    };
+    cue.timestamp = shift(cue.timestamp);
+    cue.end_timestamp = shift(cue.end_timestamp);
+    cue
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_uris() {
+        let playlist = "#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXTINF:10.0,\nseg0.vtt\n\n#EXTINF:10.0,\nsub/seg1.vtt\n#EXT-X-ENDLIST\n";
+        assert_eq!(
+            segment_uris(playlist).unwrap(),
+            vec!["seg0.vtt".to_string(), "sub/seg1.vtt".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_segment_uris_rejects_invalid_playlists() {
+        assert!(segment_uris("seg0.vtt\n").is_err());
+        assert!(segment_uris("#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1\nlow.m3u8\n").is_err());
+        assert!(segment_uris("#EXTM3U\nhttps://cdn.example/seg0.vtt\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_timestamp_map() {
+        let content = "WEBVTT\nX-TIMESTAMP-MAP=MPEGTS:900000,LOCAL:00:00:00.000\n\n00:00:01.000 --> 00:00:02.000\nHi\n";
+        assert_eq!(parse_timestamp_map(content), Some((900_000, 0)));
+
+        let content = "WEBVTT\nX-TIMESTAMP-MAP=LOCAL:00:00:05.000,MPEGTS:180000\n";
+        assert_eq!(parse_timestamp_map(content), Some((180_000, 5_000)));
+
+        // The map must appear in the header block
+        let content = "WEBVTT\n\nX-TIMESTAMP-MAP=MPEGTS:900000,LOCAL:00:00:00.000\n";
+        assert_eq!(parse_timestamp_map(content), None);
+    }
+
+    #[test]
+    fn test_shift_cue() {
+        let cue = Cue {
+            timestamp: Some("00:00:01.000".to_string()),
+            end_timestamp: Some("00:00:02.500".to_string()),
+            speaker: None,
+            text: "Hello".to_string(),
+            id: None,
+            settings: None,
+        };
+
+        let shifted = shift_cue(cue.clone(), 10_000);
+        assert_eq!(shifted.timestamp, Some("00:00:11.000".to_string()));
+        assert_eq!(shifted.end_timestamp, Some("00:00:12.500".to_string()));
+
+        let clamped = shift_cue(cue, -5_000);
+        assert_eq!(clamped.timestamp, Some("00:00:00.000".to_string()));
+    }
+
+    #[test]
+    fn test_parse_playlist_applies_offsets_and_dedups() {
+        let dir = std::env::temp_dir().join("test_hls_playlist");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("index.m3u8"),
+            "#EXTM3U\n#EXTINF:10.0,\nseg0.vtt\n#EXTINF:10.0,\nseg1.vtt\n#EXTINF:10.0,\nseg2.vtt\n#EXT-X-ENDLIST\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("seg0.vtt"),
+            "WEBVTT\nX-TIMESTAMP-MAP=MPEGTS:900000,LOCAL:00:00:00.000\n\n00:00:01.000 --> 00:00:03.000\n<v Alice>Hello.</v>\n\n00:00:09.000 --> 00:00:11.000\n<v Bob>Across the boundary.</v>\n",
+        )
+        .unwrap();
+        // Same timeline as seg0, repeating the cue that spans the boundary
+        fs::write(
+            dir.join("seg1.vtt"),
+            "WEBVTT\nX-TIMESTAMP-MAP=MPEGTS:1800000,LOCAL:00:00:10.000\n\n00:00:09.000 --> 00:00:11.000\n<v Bob>Across the boundary.</v>\n\n00:00:12.000 --> 00:00:14.000\n<v Alice>Second segment.</v>\n",
+        )
+        .unwrap();
+        // Segment-relative cue times, 20 seconds after seg0
+        fs::write(
+            dir.join("seg2.vtt"),
+            "WEBVTT\nX-TIMESTAMP-MAP=MPEGTS:2700000,LOCAL:00:00:00.000\n\n00:00:01.000 --> 00:00:02.000\n<v Bob>Third segment.</v>\n",
+        )
+        .unwrap();
+
+        let doc = parse_playlist(dir.join("index.m3u8")).unwrap();
+        assert!(doc.has_voice_tags);
+        assert_eq!(doc.cues.len(), 4);
+        assert_eq!(doc.cues[0].timestamp, Some("00:00:01.000".to_string()));
+        assert_eq!(doc.cues[1].text, "Across the boundary.");
+        assert_eq!(doc.cues[2].timestamp, Some("00:00:12.000".to_string()));
+        assert_eq!(doc.cues[3].text, "Third segment.");
+        assert_eq!(doc.cues[3].timestamp, Some("00:00:21.000".to_string()));
+        assert_eq!(doc.cues[3].end_timestamp, Some("00:00:22.000".to_string()));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,489 @@
+//! Standalone HTML transcript generation.
+//!
+//! This module renders consolidated speaker segments as a single self-contained HTML
+//! page: one `<section>` per speaker turn, a color per speaker, timestamps as anchor
+//! links, and a search box that filters turns as you type. All CSS and JavaScript are
+//! inlined so the file can be opened straight from disk.
+//!
+//! When a media file is given, the page also embeds an `<audio>` or `<video>` player:
+//! clicking a speaker turn seeks to its start, and the turn being spoken is highlighted
+//! during playback using the cue start and end times.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let html = format_html(
+//!     &segments,
+//!     &metadata,
+//!     TimestampMode::First,
+//!     &TimestampFormat::default(),
+//!     None,
+//! );
+//! assert!(html.starts_with("<!DOCTYPE html>"));
+//!
+//! // Interactive player for a local recording
+//! let html = format_html(
+//!     &segments,
+//!     &metadata,
+//!     TimestampMode::None,
+//!     &TimestampFormat::default(),
+//!     Some("meeting.mp4"),
+//! );
+//! assert!(html.contains("<video id=\"player\""));
+//! ```
+
+use crate::cli::TimestampMode;
+use crate::consolidator::{SpeakerSegment, unique_anchor};
+use crate::parser::{DocumentMetadata, unescape_markdown};
+use crate::timestamp::{TimestampFormat, parse_timestamp};
+use std::path::Path;
+
+/// Media file extensions played with an `<audio>` element; everything else gets `<video>`.
+const AUDIO_EXTENSIONS: &[&str] = &[
+    "mp3", "m4a", "aac", "wav", "flac", "oga", "ogg", "opus", "weba",
+];
+
+/// Speaker colors, assigned in order of first appearance and reused cyclically.
+const SPEAKER_COLORS: &[&str] = &[
+    "#1f77b4", "#d62728", "#2ca02c", "#9467bd", "#ff7f0e", "#17becf", "#8c564b", "#e377c2",
+];
+
+const STYLE: &str = r#"body { font-family: system-ui, -apple-system, "Segoe UI", sans-serif; line-height: 1.5; max-width: 50rem; margin: 0 auto; padding: 1rem; color: #222; }
+header.page { position: sticky; top: 0; background: #fff; padding: 0.5rem 0; border-bottom: 1px solid #ddd; }
+h1 { font-size: 1.5rem; margin: 0 0 0.5rem; }
+#search { width: 100%; box-sizing: border-box; padding: 0.4rem; font-size: 1rem; }
+#search-status { font-size: 0.85rem; color: #666; }
+.turn { border-left: 4px solid var(--speaker-color); padding: 0.25rem 0.75rem; margin: 1rem 0; }
+.turn h2 { font-size: 1rem; margin: 0; color: var(--speaker-color); }
+.turn p { margin: 0.25rem 0 0; }
+.timestamp { font-family: ui-monospace, monospace; font-size: 0.85rem; color: #666; text-decoration: none; margin-right: 0.5rem; }
+.timestamp:hover { text-decoration: underline; }
+.turn:target { background: #fff8d6; }
+.turn[hidden] { display: none; }
+#player { display: block; width: 100%; max-height: 40vh; margin-bottom: 0.5rem; background: #000; }
+audio#player { background: transparent; }
+.turn[data-start] { cursor: pointer; }
+.turn.current { background: #e8f1fb; }"#;
+
+const SCRIPT: &str = r#"(function () {
+  var input = document.getElementById("search");
+  var status = document.getElementById("search-status");
+  var turns = Array.prototype.slice.call(document.querySelectorAll(".turn"));
+  input.addEventListener("input", function () {
+    var query = input.value.trim().toLowerCase();
+    var shown = 0;
+    turns.forEach(function (turn) {
+      var match = !query || turn.textContent.toLowerCase().indexOf(query) !== -1;
+      turn.hidden = !match;
+      if (match) { shown += 1; }
+    });
+    status.textContent = query ? shown + " of " + turns.length + " turns match" : "";
+  });
+})();"#;
+
+const PLAYER_SCRIPT: &str = r#"(function () {
+  var player = document.getElementById("player");
+  var turns = Array.prototype.slice.call(document.querySelectorAll(".turn[data-start]"));
+  var current = null;
+  turns.forEach(function (turn) {
+    turn.addEventListener("click", function () {
+      player.currentTime = parseFloat(turn.dataset.start);
+      player.play();
+    });
+  });
+  player.addEventListener("timeupdate", function () {
+    var time = player.currentTime;
+    var active = null;
+    turns.forEach(function (turn) {
+      var start = parseFloat(turn.dataset.start);
+      var end = parseFloat(turn.dataset.end);
+      if (time >= start && (isNaN(end) || time < end)) { active = turn; }
+    });
+    if (active === current) { return; }
+    if (current) { current.classList.remove("current"); }
+    if (active) {
+      active.classList.add("current");
+      if (!active.hidden) { active.scrollIntoView({ block: "nearest", behavior: "smooth" }); }
+    }
+    current = active;
+  });
+})();"#;
+
+/// Format speaker segments as a standalone HTML page.
+///
+/// # Arguments
+///
+/// * `segments` - The consolidated speaker segments to format
+/// * `metadata` - Document metadata, used for the page title and language
+/// * `timestamp_mode` - How to include timestamps (None, First, or Each)
+/// * `timestamp_format` - How to display timestamps
+/// * `media` - Optional path or URL of the recording to embed as a player
+///
+/// # Returns
+///
+/// A String containing the complete HTML document.
+pub fn format_html(
+    segments: &[SpeakerSegment],
+    metadata: &DocumentMetadata,
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+    media: Option<&str>,
+) -> String {
+    let title = escape_html(&metadata.display_title());
+    let language = metadata.language.as_deref().unwrap_or("en");
+
+    let mut result = String::new();
+    result.push_str("<!DOCTYPE html>\n");
+    result.push_str(&format!("<html lang=\"{}\">\n", escape_html(language)));
+    result.push_str("<head>\n<meta charset=\"utf-8\">\n");
+    result.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    result.push_str(&format!("<title>{}</title>\n", title));
+    result.push_str(&format!("<style>\n{}\n</style>\n</head>\n<body>\n", STYLE));
+
+    result.push_str("<header class=\"page\">\n");
+    result.push_str(&format!("<h1>{}</h1>\n", title));
+    if let Some(media) = media {
+        let element = if is_audio(media) { "audio" } else { "video" };
+        result.push_str(&format!(
+            "<{0} id=\"player\" controls preload=\"metadata\" src=\"{1}\"></{0}>\n",
+            element,
+            escape_html(&media_src(media))
+        ));
+    }
+    result.push_str(
+        "<input type=\"search\" id=\"search\" placeholder=\"Search transcript\" aria-label=\"Search transcript\">\n",
+    );
+    result.push_str("<div id=\"search-status\" aria-live=\"polite\"></div>\n");
+    result.push_str("</header>\n<main>\n");
+
+    let labels = timestamp_format.turn_labels(segments, timestamp_mode);
+    let mut speakers: Vec<&str> = Vec::new();
+    let mut ids: Vec<String> = Vec::with_capacity(segments.len());
+    for (index, segment) in segments.iter().enumerate() {
+        let speaker_index = match speakers.iter().position(|s| *s == segment.speaker) {
+            Some(position) => position,
+            None => {
+                speakers.push(&segment.speaker);
+                speakers.len() - 1
+            }
+        };
+        let color = SPEAKER_COLORS[speaker_index % SPEAKER_COLORS.len()];
+        let speaker = escape_html(&unescape_markdown(&segment.speaker));
+
+        let (id, timestamp_link) = match (segment.display_timestamp(timestamp_mode), &labels[index])
+        {
+            (Some(timestamp), Some(label)) => {
+                // Turns starting at the same time get a `-2`, `-3`, ... suffix
+                let anchor = unique_anchor(&timestamp_anchor(timestamp), &ids);
+                let link = format!(
+                    "<a class=\"timestamp\" href=\"#{}\">{}</a>",
+                    anchor,
+                    escape_html(label)
+                );
+                (anchor, link)
+            }
+            _ => (
+                unique_anchor(&format!("turn-{}", index + 1), &ids),
+                String::new(),
+            ),
+        };
+        ids.push(id.clone());
+
+        // Playback times for the player, in seconds
+        let mut timing = String::new();
+        if media.is_some()
+            && let Some(start) = segment
+                .timestamps
+                .first()
+                .and_then(|ts| parse_timestamp(ts))
+        {
+            timing.push_str(&format!(" data-start=\"{}\"", seconds(start)));
+            if let Some(end) = segment.end_timestamp.as_deref().and_then(parse_timestamp) {
+                timing.push_str(&format!(" data-end=\"{}\"", seconds(end)));
+            }
+        }
+
+        result.push_str(&format!(
+            "<section class=\"turn\" id=\"{}\" data-speaker=\"{}\"{} style=\"--speaker-color: {}\">\n",
+            id, speaker, timing, color
+        ));
+        result.push_str(&format!("<h2>{}{}</h2>\n", timestamp_link, speaker));
+        result.push_str(&format!(
+            "<p>{}</p>\n",
+            timestamp_format.inline_text(segment, timestamp_mode, escape_html, "</p>\n<p>")
+        ));
+        result.push_str("</section>\n");
+    }
+
+    result.push_str("</main>\n");
+    result.push_str(&format!("<script>\n{}\n</script>\n", SCRIPT));
+    if media.is_some() {
+        result.push_str(&format!("<script>\n{}\n</script>\n", PLAYER_SCRIPT));
+    }
+    result.push_str("</body>\n</html>\n");
+
+    result
+}
+
+/// Escape text for use in HTML element content and quoted attribute values.
+pub fn escape_html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        match ch {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(ch),
+        }
+    }
+
+    result
+}
+
+/// Build an element id from a timestamp, e.g. `00:12:34.500` becomes `t-00-12-34-500`.
+fn timestamp_anchor(timestamp: &str) -> String {
+    let digits: String = timestamp
+        .chars()
+        .map(|c| if c.is_ascii_digit() { c } else { '-' })
+        .collect();
+    format!("t-{}", digits)
+}
+
+/// Format milliseconds as seconds for media element times, e.g. `1500` becomes `1.500`.
+fn seconds(ms: u64) -> String {
+    format!("{}.{:03}", ms / 1000, ms % 1000)
+}
+
+/// Whether a media path looks like an audio-only file, judging by its extension.
+fn is_audio(media: &str) -> bool {
+    let path = media.split(['?', '#']).next().unwrap_or(media);
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            AUDIO_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Turn a media path into a `src` URL.
+///
+/// URLs are used as given. Local paths get forward slashes and have the characters
+/// that would otherwise end or corrupt a URL path percent-encoded; Windows drive paths
+/// become `file:///` URLs so browsers don't read the drive letter as a scheme.
+fn media_src(media: &str) -> String {
+    if media.contains("://") {
+        return media.to_string();
+    }
+
+    let mut result = String::with_capacity(media.len());
+    let mut chars = media.chars();
+    if chars.next().is_some_and(|c| c.is_ascii_alphabetic()) && chars.next() == Some(':') {
+        result.push_str("file:///");
+    }
+    for ch in media.chars() {
+        match ch {
+            '\\' => result.push('/'),
+            '%' => result.push_str("%25"),
+            ' ' => result.push_str("%20"),
+            '#' => result.push_str("%23"),
+            '?' => result.push_str("%3F"),
+            _ => result.push(ch),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consolidator::SegmentCue;
+
+    fn segment(speaker: &str, text: &str, timestamp: &str) -> SpeakerSegment {
+        SpeakerSegment {
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+            timestamp: Some(timestamp.to_string()),
+            timestamps: vec![timestamp.to_string()],
+            end_timestamp: None,
+            cues: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("plain"), "plain");
+        assert_eq!(
+            escape_html("<b>\"Tom\" & 'Jerry'</b>"),
+            "&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn test_timestamp_anchor() {
+        assert_eq!(timestamp_anchor("00:12:34.500"), "t-00-12-34-500");
+    }
+
+    #[test]
+    fn test_format_html_inline_cue_timestamps() {
+        let cue = |timestamp: &str, text: &str| SegmentCue {
+            timestamp: Some(timestamp.to_string()),
+            end_timestamp: None,
+            text: text.to_string(),
+            new_paragraph: false,
+        };
+        let segments = vec![SpeakerSegment {
+            cues: vec![
+                cue("00:00:01.000", "Hello <all>."),
+                cue("00:00:02.000", "How are you?"),
+            ],
+            ..segment("Alice", "Hello <all>. How are you?", "00:00:01.000")
+        }];
+        let html = format_html(
+            &segments,
+            &DocumentMetadata::default(),
+            TimestampMode::Each,
+            &TimestampFormat::default(),
+            None,
+        );
+
+        assert!(html.contains("<p>Hello &lt;all&gt;. [00:00:02.000] How are you?</p>"));
+    }
+
+    #[test]
+    fn test_format_html_unique_ids() {
+        let segments = vec![
+            segment("Alice", "Hello.", "00:00:01.000"),
+            segment("Bob", "Hi.", "00:00:01.000"),
+            segment("Alice", "Same second.", "00:00:01.000"),
+        ];
+        let html = format_html(
+            &segments,
+            &DocumentMetadata::default(),
+            TimestampMode::First,
+            &TimestampFormat::default(),
+            None,
+        );
+
+        for id in ["t-00-00-01-000", "t-00-00-01-000-2", "t-00-00-01-000-3"] {
+            assert_eq!(html.matches(&format!("id=\"{}\"", id)).count(), 1);
+            assert!(html.contains(&format!("href=\"#{}\"", id)));
+        }
+    }
+
+    #[test]
+    fn test_format_html_structure() {
+        let segments = vec![
+            segment("Alice", "Hello <everyone>.", "00:00:01.000"),
+            segment("Bob", "Hi & welcome.", "00:00:05.000"),
+            segment("Alice", "Let's start.", "00:00:09.000"),
+        ];
+        let metadata = DocumentMetadata {
+            title: Some("Weekly <sync>".to_string()),
+            ..DocumentMetadata::default()
+        };
+
+        let html = format_html(
+            &segments,
+            &metadata,
+            TimestampMode::First,
+            &TimestampFormat::default(),
+            None,
+        );
+
+        assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en\">"));
+        assert!(html.contains("<title>Weekly &lt;sync&gt;</title>"));
+        assert!(html.contains("<input type=\"search\" id=\"search\""));
+        assert!(html.contains(
+            "<section class=\"turn\" id=\"t-00-00-01-000\" data-speaker=\"Alice\" style=\"--speaker-color: #1f77b4\">"
+        ));
+        assert!(html.contains(
+            "<h2><a class=\"timestamp\" href=\"#t-00-00-01-000\">00:00:01.000</a>Alice</h2>"
+        ));
+        assert!(html.contains("<p>Hello &lt;everyone&gt;.</p>"));
+        assert!(html.contains("<p>Hi &amp; welcome.</p>"));
+        // Bob gets the second color; Alice keeps her color when she speaks again
+        assert!(html.contains("data-speaker=\"Bob\" style=\"--speaker-color: #d62728\""));
+        assert!(html.contains(
+            "id=\"t-00-00-09-000\" data-speaker=\"Alice\" style=\"--speaker-color: #1f77b4\""
+        ));
+
+        // No external assets
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("src="));
+    }
+
+    #[test]
+    fn test_format_html_without_timestamps() {
+        let segments = vec![segment("John\\*Doe", "Hello.", "00:00:01.000")];
+        let html = format_html(
+            &segments,
+            &DocumentMetadata::default(),
+            TimestampMode::None,
+            &TimestampFormat::default(),
+            None,
+        );
+
+        assert!(html.contains("<title>Transcript</title>"));
+        assert!(html.contains("id=\"turn-1\" data-speaker=\"John*Doe\""));
+        assert!(html.contains("<h2>John*Doe</h2>"));
+        assert!(!html.contains("class=\"timestamp\" href"));
+    }
+
+    #[test]
+    fn test_format_html_with_media_player() {
+        let mut first = segment("Alice", "Hello.", "00:00:01.000");
+        first.end_timestamp = Some("00:00:04.250".to_string());
+        let segments = vec![first, segment("Bob", "Hi.", "00:01:05.000")];
+
+        let html = format_html(
+            &segments,
+            &DocumentMetadata::default(),
+            TimestampMode::None,
+            &TimestampFormat::default(),
+            Some("recordings/team sync.mp4"),
+        );
+
+        assert!(html.contains(
+            "<video id=\"player\" controls preload=\"metadata\" src=\"recordings/team%20sync.mp4\"></video>"
+        ));
+        assert!(html.contains("data-speaker=\"Alice\" data-start=\"1.000\" data-end=\"4.250\""));
+        // Turns without a known end stay highlighted until the next turn starts
+        assert!(html.contains("data-speaker=\"Bob\" data-start=\"65.000\" style="));
+        assert!(html.contains("player.currentTime = parseFloat(turn.dataset.start)"));
+
+        // Without media, no timing data or player script is emitted
+        let html = format_html(
+            &segments,
+            &DocumentMetadata::default(),
+            TimestampMode::None,
+            &TimestampFormat::default(),
+            None,
+        );
+        assert!(!html.contains("data-start=\""));
+        assert!(!html.contains("getElementById(\"player\")"));
+    }
+
+    #[test]
+    fn test_media_element_and_src() {
+        assert!(is_audio("podcast.MP3"));
+        assert!(is_audio("https://example.com/episode.m4a?token=abc"));
+        assert!(!is_audio("meeting.webm"));
+        assert!(!is_audio("meeting"));
+
+        assert_eq!(media_src("a b#1?.mp4"), "a%20b%231%3F.mp4");
+        assert_eq!(media_src("..\\media\\call.mp4"), "../media/call.mp4");
+        assert_eq!(
+            media_src("C:\\Videos\\call.mp4"),
+            "file:///C:/Videos/call.mp4"
+        );
+        assert_eq!(
+            media_src("https://example.com/a%20b.mp4"),
+            "https://example.com/a%20b.mp4"
+        );
+    }
+}
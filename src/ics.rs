@@ -0,0 +1,365 @@
+//! Meeting details from an iCalendar (.ics) invite.
+//!
+//! Captions don't know what the meeting was called, who organized it, or when it
+//! started. `--ics invite.ics` reads the first `VEVENT` of a calendar file and takes
+//! its `SUMMARY`, `DTSTART`, `ORGANIZER` and `ATTENDEE` properties. The summary
+//! becomes the document title and the start time anchors wall-clock timestamps.
+//!
+//! Start times are kept in the event's own time zone (`TZID`, UTC for a trailing `Z`,
+//! or floating), so no time zone database is needed; wall-clock cue times are shown
+//! in that same zone.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let event = parse_ics(Path::new("invite.ics"))?;
+//! // event.start == Some("2025-11-18T15:00:00"), event.time_zone == Some("Europe/Berlin")
+//! ```
+
+use crate::error::VttError;
+use crate::timestamp::ClockTime;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// The meeting details taken from a calendar event.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct CalendarEvent {
+    /// Event summary (the meeting subject); used as the document title
+    #[serde(skip)]
+    pub summary: Option<String>,
+    /// Start as `YYYY-MM-DDTHH:MM:SS` in the event's time zone, or `YYYY-MM-DD` for
+    /// all-day events
+    pub start: Option<String>,
+    /// `TZID` of the start time, "UTC", or `None` for floating times
+    pub time_zone: Option<String>,
+    /// Organizer's name (or address when the invite has no name)
+    pub organizer: Option<String>,
+    /// Invited attendees' names (or addresses), excluding rooms and resources
+    pub attendees: Vec<String>,
+}
+
+impl CalendarEvent {
+    /// Time of day the meeting started, in milliseconds since midnight.
+    ///
+    /// Returns `None` for all-day events and events without a start.
+    pub fn start_time_of_day(&self) -> Option<u64> {
+        let (_, time) = self.start.as_deref()?.split_once('T')?;
+        let mut parts = time.split(':').map(|part| part.parse::<u64>().ok());
+        let hours = parts.next()??;
+        let minutes = parts.next()??;
+        let seconds = parts.next()??;
+        Some(((hours * 60 + minutes) * 60 + seconds) * 1000)
+    }
+
+    /// The start time of day with its UTC offset, known only for UTC start times.
+    ///
+    /// Returns `None` for all-day events and events without a start.
+    pub fn start_clock_time(&self) -> Option<ClockTime> {
+        Some(ClockTime {
+            time_of_day: self.start_time_of_day()?,
+            utc_offset: (self.time_zone.as_deref() == Some("UTC")).then_some(0),
+        })
+    }
+
+    /// Attendees who are not among the given speakers, compared case-insensitively.
+    pub fn silent_attendees<'a>(&'a self, speakers: &[String]) -> Vec<&'a str> {
+        self.attendees
+            .iter()
+            .filter(|attendee| {
+                !speakers
+                    .iter()
+                    .any(|speaker| speaker.trim().to_lowercase() == attendee.to_lowercase())
+            })
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Read the first event from an iCalendar file.
+///
+/// # Arguments
+///
+/// * `path` - Path to the `.ics` file
+///
+/// # Returns
+///
+/// The event's details.
+///
+/// # Errors
+///
+/// Returns `VttError::FileNotFound` or `VttError::PermissionDenied` if the file can't
+/// be read, and `VttError::CalendarError` if it contains no event or the event's
+/// start time is malformed.
+pub fn parse_ics(path: &Path) -> Result<CalendarEvent, VttError> {
+    let content = fs::read_to_string(path).map_err(|e| VttError::from_read_error(path, e))?;
+
+    parse_event(&content).map_err(|reason| VttError::CalendarError {
+        path: path.to_path_buf(),
+        reason,
+    })
+}
+
+/// Parse the first `VEVENT` in iCalendar text.
+///
+/// Returns the reason on failure: no event, or a `DTSTART` that can't be read.
+fn parse_event(content: &str) -> Result<CalendarEvent, String> {
+    let mut event: Option<CalendarEvent> = None;
+    // Depth of components nested inside the event, such as VALARM
+    let mut nested = 0;
+
+    for line in unfold(content) {
+        let Some((name, params, value)) = parse_content_line(&line) else {
+            continue;
+        };
+
+        match (name.as_str(), event.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                event = Some(CalendarEvent::default());
+            }
+            ("BEGIN", Some(_)) => nested += 1,
+            ("END", Some(_)) if nested > 0 => nested -= 1,
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => break,
+            (_, Some(_)) if nested > 0 => {}
+            ("SUMMARY", Some(event)) => event.summary = Some(unescape_text(&value)),
+            ("DTSTART", Some(event)) => {
+                let (start, utc) = parse_date_time(&value)
+                    .ok_or_else(|| format!("invalid DTSTART value '{}'", value.trim()))?;
+                event.time_zone = if utc {
+                    Some("UTC".to_string())
+                } else {
+                    param(&params, "TZID")
+                };
+                event.start = Some(start);
+            }
+            ("ORGANIZER", Some(event)) => event.organizer = Some(address_name(&params, &value)),
+            ("ATTENDEE", Some(event)) => {
+                let resource = param(&params, "CUTYPE").is_some_and(|cutype| {
+                    cutype.eq_ignore_ascii_case("ROOM") || cutype.eq_ignore_ascii_case("RESOURCE")
+                });
+                if !resource {
+                    event.attendees.push(address_name(&params, &value));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    event.ok_or_else(|| "no VEVENT found".to_string())
+}
+
+/// Join folded lines: a line starting with a space or tab continues the previous one.
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(previous)) => previous.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    lines
+}
+
+/// Parameters of a content line as (upper-case name, unquoted value) pairs.
+type Params = Vec<(String, String)>;
+
+/// Split a content line into its upper-cased name, parameters, and value.
+///
+/// Parameter values may be quoted, so the `:` before the value is the first one
+/// outside quotes.
+fn parse_content_line(line: &str) -> Option<(String, Params, String)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(index, ch)| match ch {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(index),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+
+    let mut parts = split_unquoted(head, ';').into_iter();
+    let name = parts.next()?.to_ascii_uppercase();
+    let params = parts
+        .filter_map(|part| {
+            let (key, value) = part.split_once('=')?;
+            Some((
+                key.to_ascii_uppercase(),
+                value.trim_matches('"').to_string(),
+            ))
+        })
+        .collect();
+
+    Some((name, params, value.to_string()))
+}
+
+/// Split on a separator that is not inside double quotes.
+fn split_unquoted(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+
+    for (index, ch) in text.char_indices() {
+        if ch == '"' {
+            in_quotes = !in_quotes;
+        } else if ch == separator && !in_quotes {
+            parts.push(&text[start..index]);
+            start = index + 1;
+        }
+    }
+    parts.push(&text[start..]);
+
+    parts
+}
+
+/// Look up a parameter value by (upper-case) name.
+fn param(params: &[(String, String)], name: &str) -> Option<String> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.clone())
+}
+
+/// The common name of an organizer or attendee, falling back to their address.
+fn address_name(params: &[(String, String)], value: &str) -> String {
+    param(params, "CN")
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| {
+            let address = value.trim();
+            address
+                .strip_prefix("mailto:")
+                .or_else(|| address.strip_prefix("MAILTO:"))
+                .unwrap_or(address)
+                .to_string()
+        })
+}
+
+/// Parse a `DATE` or `DATE-TIME` value into an ISO 8601 string and whether it is UTC.
+fn parse_date_time(value: &str) -> Option<(String, bool)> {
+    let value = value.trim();
+    let (value, utc) = match value.strip_suffix('Z') {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    let digits = |text: &str| text.chars().all(|c| c.is_ascii_digit());
+
+    match value.split_once('T') {
+        None if value.len() == 8 && digits(value) => Some((
+            format!("{}-{}-{}", &value[..4], &value[4..6], &value[6..]),
+            false,
+        )),
+        Some((date, time))
+            if date.len() == 8 && time.len() == 6 && digits(date) && digits(time) =>
+        {
+            Some((
+                format!(
+                    "{}-{}-{}T{}:{}:{}",
+                    &date[..4],
+                    &date[4..6],
+                    &date[6..],
+                    &time[..2],
+                    &time[2..4],
+                    &time[4..]
+                ),
+                utc,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Undo iCalendar `TEXT` escaping; line breaks become spaces.
+fn unescape_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            match chars.next() {
+                Some('n' | 'N') => result.push(' '),
+                Some(next) => result.push(next),
+                None => {}
+            }
+        } else {
+            result.push(ch);
+        }
+    }
+
+    result.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVITE: &str = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+BEGIN:VTIMEZONE\r\n\
+TZID:Europe/Berlin\r\n\
+END:VTIMEZONE\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Weekly sync\\, Q3 planning\r\n\
+DTSTART;TZID=Europe/Berlin:20251118T150000\r\n\
+ORGANIZER;CN=\"Doe, Jane\":mailto:jane@example.com\r\n\
+ATTENDEE;ROLE=REQ-PARTICIPANT;CN=Alice Smith:mailto:alice@example.com\r\n\
+ATTENDEE;CUTYPE=ROOM;CN=Room 4:mailto:room4@example.com\r\n\
+ATTENDEE;RSVP=TRUE:mailto:bob@exam\r\n ple.com\r\n\
+BEGIN:VALARM\r\n\
+SUMMARY:Reminder\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    #[test]
+    fn test_parse_event() {
+        let event = parse_event(INVITE).unwrap();
+
+        assert_eq!(event.summary.as_deref(), Some("Weekly sync, Q3 planning"));
+        assert_eq!(event.start.as_deref(), Some("2025-11-18T15:00:00"));
+        assert_eq!(event.time_zone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(event.organizer.as_deref(), Some("Doe, Jane"));
+        // Rooms are skipped; folded lines are joined; addresses stand in for names
+        assert_eq!(event.attendees, vec!["Alice Smith", "bob@example.com"]);
+        assert_eq!(event.start_time_of_day(), Some(54_000_000));
+    }
+
+    #[test]
+    fn test_parse_event_start_forms() {
+        let utc = parse_event("BEGIN:VEVENT\nDTSTART:20251118T140000Z\nEND:VEVENT\n").unwrap();
+        assert_eq!(utc.start.as_deref(), Some("2025-11-18T14:00:00"));
+        assert_eq!(utc.time_zone.as_deref(), Some("UTC"));
+
+        let floating = parse_event("BEGIN:VEVENT\nDTSTART:20251118T093000\nEND:VEVENT\n").unwrap();
+        assert_eq!(floating.time_zone, None);
+        assert_eq!(floating.start_time_of_day(), Some(34_200_000));
+
+        let all_day =
+            parse_event("BEGIN:VEVENT\nDTSTART;VALUE=DATE:20251118\nEND:VEVENT\n").unwrap();
+        assert_eq!(all_day.start.as_deref(), Some("2025-11-18"));
+        assert_eq!(all_day.start_time_of_day(), None);
+
+        assert_eq!(
+            parse_event("BEGIN:VCALENDAR\nEND:VCALENDAR\n"),
+            Err("no VEVENT found".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_event_invalid_start() {
+        assert_eq!(
+            parse_event("BEGIN:VEVENT\nSUMMARY:Sync\nDTSTART:2025-11-18 15:00\nEND:VEVENT\n"),
+            Err("invalid DTSTART value '2025-11-18 15:00'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_silent_attendees() {
+        let event = parse_event(INVITE).unwrap();
+        let speakers = vec!["alice smith".to_string(), "Jane Doe".to_string()];
+
+        assert_eq!(event.silent_attendees(&speakers), vec!["bob@example.com"]);
+    }
+}
@@ -0,0 +1,325 @@
+//! Input format detection and the reader registry.
+//!
+//! Every supported input (plain WebVTT, HLS playlists, media containers) implements
+//! the [`InputFormat`] trait. The CLI asks the registry for a reader, either by name
+//! (`--from`) or by sniffing the start of the file and falling back to its extension,
+//! so adding a new reader only means implementing the trait and listing it in
+//! [`registry`].
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use vtt_to_md::input::{parse_input, InputOptions};
+//!
+//! // Detect the format from the file contents
+//! let doc = parse_input("meeting.mkv", None, &InputOptions::default())?;
+//!
+//! // Force a format regardless of contents or extension
+//! let doc = parse_input("captions.txt", Some("vtt"), &InputOptions::default())?;
+//! ```
+
+use crate::container::{self, ContainerKind};
+use crate::error::VttError;
+use crate::hls;
+use crate::parser::VttDocument;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Number of bytes read from the start of a file for content sniffing.
+const SNIFF_LEN: usize = 64;
+
+/// Reader options that apply to some input formats.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputOptions {
+    /// Subtitle track selector for media containers (see [`container::select_track`])
+    pub track: Option<String>,
+}
+
+/// A reader that turns an input file into cues plus document metadata.
+pub trait InputFormat {
+    /// Short name used with `--from` (e.g. "vtt")
+    fn name(&self) -> &'static str;
+
+    /// File extensions (lowercase, without the dot) associated with this format
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Check whether the first bytes of a file look like this format
+    fn sniff(&self, head: &[u8]) -> bool;
+
+    /// Read and parse the file
+    fn parse(&self, path: &Path, options: &InputOptions) -> Result<VttDocument, VttError>;
+
+    /// Check whether this format claims the file, by contents or by extension.
+    fn detect(&self, head: &[u8], extension: Option<&str>) -> bool {
+        self.sniff(head)
+            || extension.is_some_and(|ext| {
+                self.extensions()
+                    .iter()
+                    .any(|known| ext.eq_ignore_ascii_case(known))
+            })
+    }
+}
+
+/// Plain WebVTT files.
+pub struct WebVtt;
+
+impl InputFormat for WebVtt {
+    fn name(&self) -> &'static str {
+        "vtt"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["vtt", "webvtt"]
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        strip_bom(head).starts_with(b"WEBVTT")
+    }
+
+    fn parse(&self, path: &Path, _options: &InputOptions) -> Result<VttDocument, VttError> {
+        VttDocument::parse(path)
+    }
+}
+
+/// HLS media playlists of WebVTT segments.
+pub struct HlsPlaylist;
+
+impl InputFormat for HlsPlaylist {
+    fn name(&self) -> &'static str {
+        "hls"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["m3u8", "m3u"]
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        strip_bom(head).starts_with(b"#EXTM3U")
+    }
+
+    fn parse(&self, path: &Path, _options: &InputOptions) -> Result<VttDocument, VttError> {
+        hls::parse_playlist(path)
+    }
+}
+
+/// MP4/MOV files with embedded `wvtt` subtitle tracks.
+pub struct Mp4;
+
+impl InputFormat for Mp4 {
+    fn name(&self) -> &'static str {
+        "mp4"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["mp4", "m4v", "m4a", "mov"]
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        ContainerKind::sniff(head) == Some(ContainerKind::Mp4)
+    }
+
+    fn parse(&self, path: &Path, options: &InputOptions) -> Result<VttDocument, VttError> {
+        container::parse_media(path, ContainerKind::Mp4, options.track.as_deref())
+    }
+}
+
+/// Matroska/WebM files with embedded WebVTT subtitle tracks.
+pub struct Matroska;
+
+impl InputFormat for Matroska {
+    fn name(&self) -> &'static str {
+        "mkv"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["mkv", "mka", "mks", "webm"]
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        ContainerKind::sniff(head) == Some(ContainerKind::Matroska)
+    }
+
+    fn parse(&self, path: &Path, options: &InputOptions) -> Result<VttDocument, VttError> {
+        container::parse_media(path, ContainerKind::Matroska, options.track.as_deref())
+    }
+}
+
+/// All registered input formats, in detection order.
+///
+/// The first entry is also the fallback when nothing else matches.
+pub fn registry() -> Vec<Box<dyn InputFormat>> {
+    vec![
+        Box::new(WebVtt),
+        Box::new(HlsPlaylist),
+        Box::new(Mp4),
+        Box::new(Matroska),
+    ]
+}
+
+/// Names of all registered input formats, for `--from` validation and help text.
+pub fn format_names() -> Vec<&'static str> {
+    registry().iter().map(|format| format.name()).collect()
+}
+
+/// Look up a registered input format by name.
+///
+/// # Errors
+///
+/// Returns `VttError::UsageError` if no format has that name.
+pub fn find_format(name: &str) -> Result<Box<dyn InputFormat>, VttError> {
+    registry()
+        .into_iter()
+        .find(|format| format.name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| VttError::UsageError {
+            reason: format!(
+                "Unknown input format '{}' (expected one of: {})",
+                name,
+                format_names().join(", ")
+            ),
+        })
+}
+
+/// Pick the input format for a file.
+///
+/// Content sniffing takes priority over the file extension, so a mislabeled file is
+/// still read correctly. Files that match neither are treated as WebVTT, which
+/// produces the usual "Missing WEBVTT header" error.
+pub fn detect_format(path: &Path) -> Result<Box<dyn InputFormat>, VttError> {
+    let head = read_head(path)?;
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    let formats = registry();
+
+    let sniffed = formats.iter().position(|format| format.sniff(&head));
+    let by_extension = || {
+        formats
+            .iter()
+            .position(|format| format.detect(&[], extension))
+    };
+    let index = sniffed.or_else(by_extension).unwrap_or(0);
+
+    Ok(formats
+        .into_iter()
+        .nth(index)
+        .expect("registry is not empty"))
+}
+
+/// Parse an input file with the named format, or a detected one if `from` is `None`.
+///
+/// The returned document's metadata records the source path and the format used.
+pub fn parse_input<P: AsRef<Path>>(
+    path: P,
+    from: Option<&str>,
+    options: &InputOptions,
+) -> Result<VttDocument, VttError> {
+    let path = path.as_ref();
+    let format = match from {
+        Some(name) => find_format(name)?,
+        None => detect_format(path)?,
+    };
+
+    let mut document = format.parse(path, options)?;
+    document.metadata.source = Some(path.to_path_buf());
+    document.metadata.format = Some(format.name().to_string());
+    Ok(document)
+}
+
+/// Read up to [`SNIFF_LEN`] bytes from the start of a file.
+fn read_head(path: &Path) -> Result<Vec<u8>, VttError> {
+    let file = File::open(path).map_err(|e| VttError::from_read_error(path, e))?;
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    file.take(SNIFF_LEN as u64)
+        .read_to_end(&mut head)
+        .map_err(|e| VttError::from_read_error(path, e))?;
+    Ok(head)
+}
+
+fn strip_bom(head: &[u8]) -> &[u8] {
+    head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn detected_name(filename: &str, content: &[u8]) -> String {
+        let temp_file = std::env::temp_dir().join(filename);
+        fs::write(&temp_file, content).unwrap();
+        let name = detect_format(&temp_file).unwrap().name().to_string();
+        fs::remove_file(&temp_file).ok();
+        name
+    }
+
+    #[test]
+    fn test_detect_format_by_content() {
+        assert_eq!(
+            detected_name("test_detect_content.txt", b"WEBVTT\n\n"),
+            "vtt"
+        );
+        assert_eq!(
+            detected_name("test_detect_bom.txt", b"\xEF\xBB\xBFWEBVTT\n\n"),
+            "vtt"
+        );
+        assert_eq!(detected_name("test_detect_hls.txt", b"#EXTM3U\n"), "hls");
+        assert_eq!(
+            detected_name("test_detect_mp4.bin", b"\0\0\0\x18ftypisom\0\0\0\0"),
+            "mp4"
+        );
+        assert_eq!(
+            detected_name("test_detect_mkv.bin", &[0x1A, 0x45, 0xDF, 0xA3, 0x9F]),
+            "mkv"
+        );
+
+        // Contents win over a misleading extension
+        assert_eq!(
+            detected_name("test_detect_mislabeled.mp4", b"WEBVTT\n\n"),
+            "vtt"
+        );
+    }
+
+    #[test]
+    fn test_detect_format_by_extension() {
+        assert_eq!(detected_name("test_detect_ext.m3u8", b"\n#EXTM3U\n"), "hls");
+        assert_eq!(detected_name("test_detect_ext.webm", b""), "mkv");
+        // Unrecognized files fall back to WebVTT
+        assert_eq!(detected_name("test_detect_unknown.txt", b"hello"), "vtt");
+    }
+
+    #[test]
+    fn test_find_format() {
+        assert_eq!(find_format("hls").unwrap().name(), "hls");
+        assert_eq!(find_format("MKV").unwrap().name(), "mkv");
+
+        match find_format("srt") {
+            Err(VttError::UsageError { reason }) => {
+                assert!(reason.contains("vtt, hls, mp4, mkv"));
+            }
+            _ => panic!("Expected UsageError for unknown format"),
+        }
+    }
+
+    #[test]
+    fn test_parse_input_records_metadata() {
+        let temp_file = std::env::temp_dir().join("test_parse_input.txt");
+        fs::write(
+            &temp_file,
+            "WEBVTT - Standup\n\n00:00:01.000 --> 00:00:02.000\n<v Alice>Hi</v>\n",
+        )
+        .unwrap();
+
+        let doc = parse_input(&temp_file, Some("vtt"), &InputOptions::default()).unwrap();
+        assert_eq!(doc.cues.len(), 1);
+        assert_eq!(doc.metadata.title, Some("Standup".to_string()));
+        assert_eq!(doc.metadata.format, Some("vtt".to_string()));
+        assert_eq!(doc.metadata.source, Some(temp_file.clone()));
+
+        fs::remove_file(&temp_file).ok();
+    }
+
+    #[test]
+    fn test_parse_input_file_not_found() {
+        let result = parse_input("nonexistent_input.vtt", None, &InputOptions::default());
+        assert!(matches!(result, Err(VttError::FileNotFound { .. })));
+    }
+}
@@ -0,0 +1,242 @@
+//! JSON and JSON Lines transcript output.
+//!
+//! Both formats follow a small versioned schema so downstream jobs can detect
+//! incompatible changes. [`SCHEMA_VERSION`] is bumped whenever a field is renamed,
+//! removed or changes meaning; adding fields does not bump it.
+//!
+//! JSON output is a single object:
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "metadata": { "title": null, "language": null, "source": "meeting.vtt", "format": "vtt" },
+//!   "records": "segments",
+//!   "segments": [
+//!     { "speaker": "Alice", "text": "Hello everyone.", "timestamps": ["00:00:01.000"], "end_timestamp": "00:00:03.000" }
+//!   ]
+//! }
+//! ```
+//!
+//! With cue records, `"records"` is `"cues"` and the array is named `cues`, each entry
+//! holding `timestamp`, `end_timestamp`, `speaker`, `text`, `id` and `settings`. JSON Lines output puts the
+//! same information on separate lines, each tagged with a `type`: a `metadata` line
+//! first, then one `segment` or `cue` line per record.
+
+use crate::cli::RecordKind;
+use crate::consolidator::SpeakerSegment;
+use crate::error::VttError;
+use crate::parser::{Cue, DocumentMetadata};
+use serde::Serialize;
+use std::io;
+
+/// Version of the JSON and JSON Lines output schema.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The records being written, either consolidated segments or individual cues.
+#[derive(Debug, Clone, Copy)]
+pub enum Records<'a> {
+    /// Consolidated speaker turns
+    Segments(&'a [SpeakerSegment]),
+    /// Individual caption cues
+    Cues(&'a [Cue]),
+}
+
+impl Records<'_> {
+    fn kind(&self) -> RecordKind {
+        match self {
+            Records::Segments(_) => RecordKind::Segments,
+            Records::Cues(_) => RecordKind::Cues,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonDocument<'a> {
+    schema_version: u32,
+    metadata: &'a DocumentMetadata,
+    records: RecordKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segments: Option<&'a [SpeakerSegment]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cues: Option<&'a [Cue]>,
+}
+
+/// A single JSON Lines entry, tagged with its `type`.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum JsonLine<'a> {
+    Metadata {
+        schema_version: u32,
+        records: RecordKind,
+        #[serde(flatten)]
+        metadata: &'a DocumentMetadata,
+    },
+    Segment(&'a SpeakerSegment),
+    Cue(&'a Cue),
+}
+
+/// Format records as a pretty-printed JSON document.
+///
+/// # Arguments
+///
+/// * `records` - The segments or cues to write
+/// * `metadata` - Document metadata to include alongside the records
+///
+/// # Returns
+///
+/// A String containing the JSON document, ending with a newline.
+pub fn format_json(records: Records, metadata: &DocumentMetadata) -> Result<String, VttError> {
+    let (segments, cues) = match records {
+        Records::Segments(segments) => (Some(segments), None),
+        Records::Cues(cues) => (None, Some(cues)),
+    };
+    let document = JsonDocument {
+        schema_version: SCHEMA_VERSION,
+        metadata,
+        records: records.kind(),
+        segments,
+        cues,
+    };
+
+    let mut result = serde_json::to_string_pretty(&document).map_err(io::Error::from)?;
+    result.push('\n');
+    Ok(result)
+}
+
+/// Format records as JSON Lines: a metadata line followed by one line per record.
+///
+/// # Arguments
+///
+/// * `records` - The segments or cues to write
+/// * `metadata` - Document metadata for the first line
+///
+/// # Returns
+///
+/// A String with one JSON object per line.
+pub fn format_jsonl(records: Records, metadata: &DocumentMetadata) -> Result<String, VttError> {
+    let mut lines = vec![JsonLine::Metadata {
+        schema_version: SCHEMA_VERSION,
+        records: records.kind(),
+        metadata,
+    }];
+    match records {
+        Records::Segments(segments) => lines.extend(segments.iter().map(JsonLine::Segment)),
+        Records::Cues(cues) => lines.extend(cues.iter().map(JsonLine::Cue)),
+    }
+
+    let mut result = String::new();
+    for line in &lines {
+        result.push_str(&serde_json::to_string(line).map_err(io::Error::from)?);
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn sample_segments() -> Vec<SpeakerSegment> {
+        vec![SpeakerSegment {
+            speaker: "John\\*Doe".to_string(),
+            text: "Hello everyone.".to_string(),
+            timestamp: None,
+            timestamps: vec!["00:00:01.000".to_string(), "00:00:02.000".to_string()],
+            end_timestamp: Some("00:00:03.000".to_string()),
+            cues: Vec::new(),
+        }]
+    }
+
+    fn sample_cues() -> Vec<Cue> {
+        vec![
+            Cue {
+                timestamp: Some("00:00:01.000".to_string()),
+                end_timestamp: Some("00:00:02.000".to_string()),
+                speaker: Some("Alice".to_string()),
+                text: "Hi.".to_string(),
+                id: None,
+                settings: None,
+            },
+            Cue {
+                timestamp: Some("00:00:02.000".to_string()),
+                end_timestamp: Some("00:00:03.000".to_string()),
+                speaker: None,
+                text: "Music".to_string(),
+                id: None,
+                settings: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_format_json_segments() {
+        let segments = sample_segments();
+        let metadata = DocumentMetadata {
+            format: Some("vtt".to_string()),
+            ..DocumentMetadata::default()
+        };
+
+        let output = format_json(Records::Segments(&segments), &metadata).unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(
+            value,
+            json!({
+                "schema_version": SCHEMA_VERSION,
+                "metadata": { "title": null, "language": null, "source": null, "format": "vtt" },
+                "records": "segments",
+                "segments": [{
+                    "speaker": "John*Doe",
+                    "text": "Hello everyone.",
+                    "timestamps": ["00:00:01.000", "00:00:02.000"],
+                    "end_timestamp": "00:00:03.000"
+                }]
+            })
+        );
+        assert!(output.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_format_json_cues() {
+        let cues = sample_cues();
+        let output = format_json(Records::Cues(&cues), &DocumentMetadata::default()).unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(value["records"], "cues");
+        assert!(value.get("segments").is_none());
+        assert_eq!(value["cues"][0]["speaker"], "Alice");
+        assert_eq!(value["cues"][1]["speaker"], Value::Null);
+        assert_eq!(value["cues"][1]["end_timestamp"], "00:00:03.000");
+    }
+
+    #[test]
+    fn test_format_jsonl() {
+        let cues = sample_cues();
+        let metadata = DocumentMetadata {
+            title: Some("Standup".to_string()),
+            ..DocumentMetadata::default()
+        };
+
+        let output = format_jsonl(Records::Cues(&cues), &metadata).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["type"], "metadata");
+        assert_eq!(lines[0]["schema_version"], SCHEMA_VERSION);
+        assert_eq!(lines[0]["records"], "cues");
+        assert_eq!(lines[0]["title"], "Standup");
+        assert_eq!(lines[1]["type"], "cue");
+        assert_eq!(lines[1]["text"], "Hi.");
+        assert_eq!(lines[2]["type"], "cue");
+
+        let segments = sample_segments();
+        let output = format_jsonl(Records::Segments(&segments), &metadata).unwrap();
+        let last: Value = serde_json::from_str(output.lines().last().unwrap()).unwrap();
+        assert_eq!(last["type"], "segment");
+        assert_eq!(last["speaker"], "John*Doe");
+    }
+}
@@ -1,10 +1,29 @@
 //! VTT to Markdown converter - command-line tool for converting WebVTT transcripts to readable Markdown.
 
+mod captions;
+mod chapters;
 mod cli;
 mod consolidator;
+mod container;
+mod csv;
+mod docx;
 mod error;
+mod fountain;
+mod front_matter;
+mod hls;
+mod html;
+mod ics;
+mod input;
+mod json;
 mod markdown;
+mod markup;
+mod media_link;
+mod output;
+mod pandoc;
 mod parser;
+mod template;
+mod timestamp;
+mod topics;
 
 use clap::Parser;
 use cli::Args;
@@ -37,8 +56,25 @@ fn main() -> ExitCode {
 
 /// Run the VTT to Markdown conversion pipeline.
 fn run_conversion(args: &Args) -> Result<(), error::VttError> {
-    // Parse the VTT file
-    let vtt_document = parser::VttDocument::parse(&args.input)?;
+    // Parse the input with the requested or detected format
+    let input_options = input::InputOptions {
+        track: args.track.clone(),
+    };
+    let mut vtt_document = input::parse_input(&args.input, args.from.as_deref(), &input_options)?;
+
+    // Take the title and meeting details from the calendar invite, if given
+    if let Some(ref ics_path) = args.ics {
+        let event = ics::parse_ics(ics_path)?;
+        if let Some(ref summary) = event.summary {
+            vtt_document.metadata.title = Some(summary.clone());
+        }
+        vtt_document.metadata.meeting = Some(event);
+    }
+
+    // Read the section titles from the chapters track, if given
+    if let Some(ref chapters_path) = args.chapters {
+        vtt_document.metadata.chapters = chapters::read_chapters(chapters_path)?;
+    }
 
     // Determine if we should filter unknown speakers:
     // - Explicitly enabled with --filter-unknown
@@ -57,21 +93,114 @@ fn run_conversion(args: &Args) -> Result<(), error::VttError> {
         vtt_document.cues
     };
 
-    // Consolidate speaker segments
-    let segments = consolidator::consolidate_cues(
-        &cues,
-        &args.unknown_speaker,
-        args.include_timestamps,
+    // Consolidate speaker segments, never across a chapter boundary
+    let mut segments = if vtt_document.metadata.chapters.is_empty() {
+        consolidator::consolidate_cues(
+            &cues,
+            &args.unknown_speaker,
+            args.include_timestamps,
+            args.max_gap,
+        )
+    } else {
+        chapters::consolidate_by_chapter(
+            &cues,
+            &vtt_document.metadata.chapters,
+            &args.unknown_speaker,
+            args.include_timestamps,
+            args.max_gap,
+        )
+    };
+
+    // Break long turns into paragraphs
+    consolidator::split_paragraphs(
+        &mut segments,
+        &consolidator::ParagraphRules {
+            max_sentences: args.paragraph_sentences,
+            max_words: args.paragraph_words,
+            max_pause: args.paragraph_pause,
+        },
     );
 
-    // Format as Markdown
-    let markdown_content = markdown::format_markdown(&segments, args.include_timestamps);
+    // Find sections in the consolidated turns instead of reading chapters
+    if args.topics {
+        vtt_document.metadata.chapters = topics::segment_topics(&segments);
+    }
+
+    // Render with the selected output format
+    let output_format = output::find_format(&args.to)?;
+    let transcript = output::Transcript {
+        segments: &segments,
+        cues: &cues,
+        metadata: &vtt_document.metadata,
+    };
+    // Wall-clock timestamps count from --start-time, or else the invite's start
+    let clock_anchor = args.start_time.or_else(|| {
+        vtt_document
+            .metadata
+            .meeting
+            .as_ref()
+            .and_then(ics::CalendarEvent::start_clock_time)
+    });
+    let clock_start = match args.time_zone {
+        None => clock_anchor.map(|anchor| anchor.time_of_day),
+        Some(zone) => Some(
+            clock_anchor
+                .and_then(|anchor| anchor.in_zone(zone))
+                .ok_or_else(|| error::VttError::UsageError {
+                    reason: "--time-zone requires a start time with a UTC offset (--start-time with Z or +HH:MM, or an --ics start in UTC)".to_string(),
+                })?,
+        ),
+    };
+    let timestamp_format = timestamp::TimestampFormat {
+        precision: args.timestamp_precision,
+        offset: args.timestamp_offset.unwrap_or_default(),
+        clock_start,
+        relative: args.relative_timestamps,
+    };
+    let render_options = output::RenderOptions {
+        timestamp_mode: args.include_timestamps,
+        timestamp_format,
+        media: args.media.clone(),
+        media_link: args
+            .media_url
+            .as_deref()
+            .map(|url| media_link::MediaLink::new(url, args.link_style)),
+        records: args.records.unwrap_or_default(),
+        interviewers: args.interviewer.clone(),
+        columns: args.columns.clone(),
+        markdown_layout: markdown::MarkdownLayout {
+            style: args.style.unwrap_or_default(),
+            separator: args.separator,
+            compact: args.compact,
+            anchors: args.anchors,
+            index: args.index,
+            toc: args.toc,
+            pause_markers: if args.pause_markers { args.max_gap } else { None },
+        },
+        front_matter: if args.front_matter {
+            Some(front_matter::FrontMatter::collect(&args.input, &transcript)?.to_yaml())
+        } else {
+            None
+        },
+    };
+    let content = match args.template {
+        Some(ref template_path) => {
+            template::render_template(
+                template_path,
+                &transcript,
+                args.include_timestamps,
+                &render_options.timestamp_format,
+            )?
+            .into_bytes()
+        }
+        None => output_format.render(&transcript, &render_options)?,
+    };
 
     // Write output (either to file or stdout)
     if args.stdout {
-        markdown::write_markdown_stdout(&markdown_content)?;
+        output::write_output_stdout(&content)?;
     } else if let Some(output_path) = args.get_output_path() {
-        markdown::write_markdown_file(&markdown_content, output_path, args.force, args.no_clobber)?;
+        output::write_output_file(&content, output_path, args.force, args.no_clobber)?;
     }
 
     Ok(())
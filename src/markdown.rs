@@ -1,26 +1,65 @@
-//! Markdown generation and file output.
+//! Markdown generation.
 //!
 //! This module handles formatting consolidated speaker segments into Markdown format
-//! (bold speaker names followed by text) and writing the output to files or stdout.
-//! It includes safeguards for file overwriting and proper permission handling.
+//! (bold speaker names followed by text by default, or headings, blockquotes,
+//! definition lists, tables or a chat log), including the interview Q&A layout. Writing
+//! the result to a file or stdout is handled by the [`output`](crate::output) module.
 
-use crate::cli::TimestampMode;
-use crate::consolidator::SpeakerSegment;
-use crate::error::VttError;
-use std::fs;
-use std::io::{self, Write};
-use std::path::Path;
+use crate::chapters::{Chapter, chapter_breaks};
+use crate::cli::{MarkdownStyle, TimestampMode};
+use crate::consolidator::{SpeakerSegment, anchor_ids, pause_before};
+use crate::media_link::MediaLink;
+use crate::parser::{DocumentMetadata, escape_markdown, unescape_markdown};
+use crate::timestamp::{TimestampFormat, format_duration};
+
+/// How speaker turns are laid out in Markdown output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MarkdownLayout {
+    /// The layout of each speaker turn
+    pub style: MarkdownStyle,
+    /// Put a thematic break between turns
+    pub separator: bool,
+    /// Separate turns with a single line break instead of a blank line
+    pub compact: bool,
+    /// Give each turn a stable `<a id>` anchor from its start time and speaker
+    pub anchors: bool,
+    /// Start with an index of turns linking to their anchors
+    pub index: bool,
+    /// Start with a table of contents linking to the chapter headings
+    pub toc: bool,
+    /// Mark silences longer than this many milliseconds before the next turn
+    pub pause_markers: Option<u64>,
+}
 
 /// Format speaker segments as Markdown text.
 ///
-/// Each segment is formatted as `**SpeakerName:** text` with double newlines
-/// between segments. When timestamps are included, they're prepended as
-/// `[HH:MM:SS.mmm] **SpeakerName:** text`.
+/// With the default layout, each segment is formatted as `**SpeakerName:** text`
+/// with double newlines between segments. When timestamps are included, they're
+/// prepended as `[HH:MM:SS.mmm] **SpeakerName:** text`.
+///
+/// Each [`MarkdownStyle`] lays out a turn differently; the timestamp, when the mode
+/// includes one, is written as `[HH:MM:SS.mmm]` in front of the speaker name (or in
+/// its own column for tables), and links into the recording when `media_link` is
+/// given. Turns are separated by a blank line, or a single line
+/// break with `compact`, and optionally by a thematic break. Table rows are always
+/// consecutive, since a table cannot be interrupted.
+///
+/// With `anchors` (or `index`), each turn starts with an `<a id="t-00-12-34-alice">`
+/// anchor (see [`anchor_ids`]), and `index` puts a linked list of turns first.
+///
+/// Each chapter starts with a `## <a id="chapter-intro"></a>Intro` heading before
+/// its first turn; `toc` puts a linked list of the chapters first. With
+/// `pause_markers`, a turn that follows a longer silence is preceded by a
+/// `[pause 3m12s]` marker.
 ///
 /// # Arguments
 ///
 /// * `segments` - The consolidated speaker segments to format
 /// * `timestamp_mode` - How to include timestamps (None, First, or Each)
+/// * `timestamp_format` - How to display timestamps
+/// * `media_link` - Recording to link timestamps to, if any
+/// * `chapters` - Chapters to head sections with, in order of start time
+/// * `layout` - The style and spacing of speaker turns
 ///
 /// # Returns
 ///
@@ -35,132 +74,481 @@ use std::path::Path;
 ///         text: "Hello world.".to_string(),
 ///         timestamp: None,
 ///         timestamps: vec![],
+///         end_timestamp: None,
+///         cues: vec![],
 ///     },
 /// ];
-/// let markdown = format_markdown(&segments, TimestampMode::None);
+/// let markdown = format_markdown(
+///     &segments,
+///     TimestampMode::None,
+///     &TimestampFormat::default(),
+///     None,
+///     &[],
+///     &MarkdownLayout::default(),
+/// );
 /// // Result: "**Alice:** Hello world.\n\n"
+///
+/// let layout = MarkdownLayout { style: MarkdownStyle::Heading, ..MarkdownLayout::default() };
+/// let markdown = format_markdown(
+///     &segments,
+///     TimestampMode::None,
+///     &TimestampFormat::default(),
+///     None,
+///     &[],
+///     &layout,
+/// );
+/// // Result: "### Alice\n\nHello world.\n\n"
 /// ```
-pub fn format_markdown(segments: &[SpeakerSegment], timestamp_mode: TimestampMode) -> String {
-    let mut result = String::new();
+pub fn format_markdown(
+    segments: &[SpeakerSegment],
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+    media_link: Option<&MediaLink>,
+    chapters: &[Chapter],
+    layout: &MarkdownLayout,
+) -> String {
+    let anchors = if layout.anchors || layout.index {
+        anchor_ids(segments)
+    } else {
+        Vec::new()
+    };
+    let mut result = if layout.toc {
+        format_toc(chapters)
+    } else {
+        String::new()
+    };
+    if layout.index {
+        result.push_str(&format_index(segments, &anchors, timestamp_format));
+    }
+
+    let breaks = chapter_breaks(chapters, segments);
+    if layout.style == MarkdownStyle::Table {
+        result.push_str(&format_table(
+            segments,
+            timestamp_mode,
+            timestamp_format,
+            media_link,
+            &anchors,
+            &breaks,
+            layout.pause_markers,
+        ));
+        return result;
+    }
 
-    for segment in segments {
-        match timestamp_mode {
-            TimestampMode::None => {
-                result.push_str(&format!("**{}:** {}\n\n", segment.speaker, segment.text));
+    let line_break = if layout.compact { "\n" } else { "\n\n" };
+    let labels = timestamp_format.turn_labels(segments, timestamp_mode);
+
+    for (index, segment) in segments.iter().enumerate() {
+        result.push_str(&chapter_headings(&breaks[index]));
+
+        // A heading already separates the turn from the one before
+        if index > 0 && layout.separator && breaks[index].is_empty() {
+            // `***` rather than `---`, which would turn a preceding text line into a
+            // heading when turns are not separated by a blank line
+            result.push_str("***");
+            result.push_str(line_break);
+        }
+        if let Some(marker) = pause_marker(segments, index, layout.pause_markers) {
+            // Keep the marker inside the list or quote the turns are laid out in
+            match layout.style {
+                MarkdownStyle::Blockquote => result.push_str("> "),
+                MarkdownStyle::Chat => result.push_str("- "),
+                _ => {}
             }
-            TimestampMode::First => {
-                if let Some(ref timestamp) = segment.timestamp {
-                    result.push_str(&format!(
-                        "[{}] **{}:** {}\n\n",
-                        timestamp, segment.speaker, segment.text
-                    ));
-                } else {
-                    result.push_str(&format!("**{}:** {}\n\n", segment.speaker, segment.text));
-                }
+            result.push_str(&marker);
+            result.push_str(line_break);
+        }
+
+        let mut prefix = anchor_tag(&anchors, index);
+        if let (Some(label), Some(timestamp)) =
+            (&labels[index], segment.display_timestamp(timestamp_mode))
+        {
+            prefix.push_str(&format!(
+                "{} ",
+                timestamp_label(label, timestamp, media_link)
+            ));
+        }
+        // Later paragraphs of the turn stay inside its quote, definition or list item
+        let paragraph_break = match layout.style {
+            MarkdownStyle::Blockquote if layout.compact => "\n> ".to_string(),
+            MarkdownStyle::Blockquote => "\n>\n> ".to_string(),
+            MarkdownStyle::Definition => format!("{}    ", line_break),
+            MarkdownStyle::Chat => format!("{}  ", line_break),
+            _ => line_break.to_string(),
+        };
+        let text = inline_text(
+            segment,
+            timestamp_mode,
+            timestamp_format,
+            media_link,
+            &paragraph_break,
+        );
+
+        match layout.style {
+            MarkdownStyle::Bold => {
+                result.push_str(&format!("{}**{}:** {}", prefix, segment.speaker, text));
             }
-            TimestampMode::Each => {
-                // TimestampMode::Each displays the first timestamp for each speaker segment
-                // with the full consolidated text. This is a simplified implementation that
-                // shows when the speaker turn began rather than splitting text by original
-                // cue boundaries (which are lost during consolidation).
-                // This aligns with the consolidator's text joining strategy.
-                if !segment.timestamps.is_empty() {
-                    result.push_str(&format!(
-                        "[{}] **{}:** {}\n\n",
-                        segment.timestamps[0], segment.speaker, segment.text
-                    ));
-                } else {
-                    result.push_str(&format!("**{}:** {}\n\n", segment.speaker, segment.text));
-                }
+            MarkdownStyle::Heading => {
+                result.push_str(&format!("### {}{}", prefix, segment.speaker));
+                result.push_str(line_break);
+                result.push_str(&text);
+            }
+            MarkdownStyle::Blockquote => {
+                result.push_str(&format!("> {}**{}:** {}", prefix, segment.speaker, text));
+            }
+            MarkdownStyle::Definition => {
+                result.push_str(&format!("{}{}\n:   {}", prefix, segment.speaker, text));
             }
+            MarkdownStyle::Chat => {
+                result.push_str(&format!("- {}**{}:** {}", prefix, segment.speaker, text));
+            }
+            MarkdownStyle::Table => unreachable!("tables are laid out by format_table"),
         }
+        result.push_str(line_break);
     }
+    result.push_str(&chapter_headings(&breaks[segments.len()]));
 
     result
 }
 
-/// Write Markdown content to a file with appropriate safeguards.
+/// Lay out speaker turns as a Markdown table, with a time column when the mode
+/// includes timestamps. Anchors, if any, go in each row's first cell, a pause marker
+/// gets a row of its own, and a chapter heading ends the table and starts a new one
+/// after it.
+fn format_table(
+    segments: &[SpeakerSegment],
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+    media_link: Option<&MediaLink>,
+    anchors: &[String],
+    breaks: &[Vec<&Chapter>],
+    pause_markers: Option<u64>,
+) -> String {
+    let with_time = timestamp_mode != TimestampMode::None;
+    let header = if with_time {
+        "| Time | Speaker | Text |\n| --- | --- | --- |\n"
+    } else {
+        "| Speaker | Text |\n| --- | --- |\n"
+    };
+    let mut result = String::new();
+    let mut in_table = false;
+    if breaks[0].is_empty() {
+        result.push_str(header);
+        in_table = true;
+    }
+
+    let labels = timestamp_format.turn_labels(segments, timestamp_mode);
+    for (index, (segment, label)) in segments.iter().zip(&labels).enumerate() {
+        if !breaks[index].is_empty() {
+            if in_table {
+                result.push('\n');
+            }
+            result.push_str(&chapter_headings(&breaks[index]));
+            result.push_str(header);
+            in_table = true;
+        }
+        if let Some(marker) = pause_marker(segments, index, pause_markers) {
+            let empty_cells = if with_time { "| | " } else { "| " };
+            result.push_str(&format!("{}| {} |\n", empty_cells, marker));
+        }
+
+        result.push_str("| ");
+        result.push_str(&anchor_tag(anchors, index));
+        if with_time {
+            let time = match (label, segment.display_timestamp(timestamp_mode)) {
+                (Some(label), Some(timestamp)) => {
+                    match media_link.and_then(|link| link.at(timestamp)) {
+                        Some(url) => format!("[{}]({})", label, url),
+                        None => label.clone(),
+                    }
+                }
+                _ => String::new(),
+            };
+            result.push_str(&format!("{} | ", time));
+        }
+        // Speaker names are already escaped; pipes and line breaks in the text would
+        // end the cell or the row
+        let text = inline_text(
+            segment,
+            timestamp_mode,
+            timestamp_format,
+            media_link,
+            "<br><br>",
+        )
+        .replace('|', "\\|")
+        .replace('\n', " ");
+        result.push_str(&format!("{} | {} |\n", segment.speaker, text));
+    }
+
+    if in_table {
+        result.push('\n');
+    }
+    result.push_str(&chapter_headings(&breaks[segments.len()]));
+    result
+}
+
+/// Format the meeting header for a transcript with calendar details (`--ics`).
 ///
-/// This function checks if the output file exists and respects the
-/// --force and --no-clobber flags. It handles permission errors and
-/// other I/O errors appropriately.
+/// The header is the title as a heading followed by a list with the start time,
+/// organizer, attendees, and the attendees who never spoke. Without meeting details
+/// it is empty.
 ///
 /// # Arguments
 ///
-/// * `content` - The Markdown content to write
-/// * `output_path` - The path to write to
-/// * `force` - Whether to overwrite existing files
-/// * `no_clobber` - Whether to skip if file exists
+/// * `metadata` - Document metadata, with the meeting details and title
+/// * `segments` - The consolidated speaker segments, to find silent attendees
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` if successful, or `Err(VttError)` if:
-/// - File exists and --force not set (OutputExists)
-/// - Permission denied (PermissionDenied)
-/// - Other I/O errors (WriteError)
-///
-/// # Example
-///
-/// ```rust,ignore
-/// write_markdown_file("**Alice:** Hello", Path::new("output.md"), false, false)?;
-/// ```
-pub fn write_markdown_file(
-    content: &str,
-    output_path: &Path,
-    force: bool,
-    no_clobber: bool,
-) -> Result<(), VttError> {
-    // Check if output file exists
-    if output_path.exists() {
-        if no_clobber {
-            // Skip silently (this is success case for --no-clobber)
-            return Ok(());
-        }
-        if !force {
-            return Err(VttError::OutputExists {
-                path: output_path.to_path_buf(),
-            });
+/// A String containing the header followed by a blank line, or an empty String.
+pub fn format_meeting_header(metadata: &DocumentMetadata, segments: &[SpeakerSegment]) -> String {
+    let Some(ref meeting) = metadata.meeting else {
+        return String::new();
+    };
+    let names = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| escape_markdown(name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut result = format!("# {}\n\n", escape_markdown(&metadata.display_title()));
+
+    if let Some(ref start) = meeting.start {
+        result.push_str(&format!("- **When:** {}", start.replacen('T', " ", 1)));
+        if let Some(ref time_zone) = meeting.time_zone {
+            result.push_str(&format!(" ({})", time_zone));
         }
-        // If force is true, we'll overwrite
+        result.push('\n');
     }
+    if let Some(ref organizer) = meeting.organizer {
+        result.push_str(&format!(
+            "- **Organizer:** {}\n",
+            escape_markdown(organizer)
+        ));
+    }
+    if !meeting.attendees.is_empty() {
+        let attendees: Vec<&str> = meeting.attendees.iter().map(String::as_str).collect();
+        result.push_str(&format!("- **Attendees:** {}\n", names(&attendees)));
 
-    // Write the file
-    fs::write(output_path, content).map_err(|e| {
-        if e.kind() == io::ErrorKind::PermissionDenied {
-            VttError::PermissionDenied {
-                path: output_path.to_path_buf(),
-            }
-        } else {
-            VttError::WriteError {
-                path: output_path.to_path_buf(),
-                source: e,
-            }
+        let speakers: Vec<String> = segments
+            .iter()
+            .map(|segment| unescape_markdown(&segment.speaker))
+            .collect();
+        let silent = meeting.silent_attendees(&speakers);
+        if !silent.is_empty() {
+            result.push_str(&format!("- **Did not speak:** {}\n", names(&silent)));
         }
-    })?;
+    }
 
-    Ok(())
+    result.push('\n');
+    result
 }
 
-/// Write Markdown content to stdout.
+/// Format speaker segments as an interview in Markdown Q&A form.
+///
+/// Turns by an interviewer become `**Q:** text` and everyone else's become
+/// `**A:** text`, with the same timestamp prefix as [`format_markdown`]. Interviewer
+/// names are matched case-insensitively; if none are given, the first speaker is
+/// taken to be the interviewer.
 ///
 /// # Arguments
 ///
-/// * `content` - The Markdown content to print
+/// * `segments` - The consolidated speaker segments to format
+/// * `timestamp_mode` - How to include timestamps (None, First, or Each)
+/// * `timestamp_format` - How to display timestamps
+/// * `media_link` - Recording to link timestamps to, if any
+/// * `interviewers` - Names of the speakers asking the questions
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` if successful, or `Err(VttError)` for I/O errors.
-pub fn write_markdown_stdout(content: &str) -> Result<(), VttError> {
-    io::stdout()
-        .write_all(content.as_bytes())
-        .map_err(VttError::IoError)?;
-    Ok(())
+/// A String containing the formatted Markdown content.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let markdown = format_qa(
+///     &segments,
+///     TimestampMode::None,
+///     &TimestampFormat::default(),
+///     None,
+///     &["Alice".to_string()],
+/// );
+/// // Result: "**Q:** How did you start?\n\n**A:** By accident.\n\n"
+/// ```
+pub fn format_qa(
+    segments: &[SpeakerSegment],
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+    media_link: Option<&MediaLink>,
+    interviewers: &[String],
+) -> String {
+    let first_speaker = segments.first().map(|segment| segment.speaker.as_str());
+    let is_interviewer = |speaker: &str| {
+        if interviewers.is_empty() {
+            Some(speaker) == first_speaker
+        } else {
+            let name = unescape_markdown(speaker);
+            interviewers
+                .iter()
+                .any(|interviewer| interviewer.trim().eq_ignore_ascii_case(&name))
+        }
+    };
+
+    let mut result = String::new();
+
+    let labels = timestamp_format.turn_labels(segments, timestamp_mode);
+    for (segment, label) in segments.iter().zip(&labels) {
+        if let (Some(label), Some(timestamp)) = (label, segment.display_timestamp(timestamp_mode)) {
+            result.push_str(&format!(
+                "{} ",
+                timestamp_label(label, timestamp, media_link)
+            ));
+        }
+        let label = if is_interviewer(&segment.speaker) {
+            "Q"
+        } else {
+            "A"
+        };
+        result.push_str(&format!(
+            "**{}:** {}\n\n",
+            label,
+            inline_text(
+                segment,
+                timestamp_mode,
+                timestamp_format,
+                media_link,
+                "\n\n"
+            )
+        ));
+    }
+
+    result
+}
+
+/// An index of speaker turns: a list of links to their anchors, under an `## Index`
+/// heading.
+fn format_index(
+    segments: &[SpeakerSegment],
+    anchors: &[String],
+    timestamp_format: &TimestampFormat,
+) -> String {
+    let mut result = String::from("## Index\n\n");
+
+    for (segment, anchor) in segments.iter().zip(anchors) {
+        let text = match segment.timestamps.first() {
+            Some(timestamp) => format!(
+                "{} {}",
+                timestamp_format.display(timestamp),
+                segment.speaker
+            ),
+            None => segment.speaker.clone(),
+        };
+        result.push_str(&format!("- [{}](#{})\n", text, anchor));
+    }
+
+    result.push('\n');
+    result
+}
+
+/// A linked table of contents of the chapters (`--toc`).
+fn format_toc(chapters: &[Chapter]) -> String {
+    let mut result = String::from("## Contents\n\n");
+
+    for chapter in chapters {
+        result.push_str(&format!(
+            "- [{}](#{})\n",
+            escape_markdown(&chapter.title),
+            chapter.id
+        ));
+    }
+
+    result.push('\n');
+    result
+}
+
+/// The `##` headings of chapters starting before a turn, each with its anchor.
+fn chapter_headings(chapters: &[&Chapter]) -> String {
+    chapters
+        .iter()
+        .map(|chapter| {
+            format!(
+                "## <a id=\"{}\"></a>{}\n\n",
+                chapter.id,
+                escape_markdown(&chapter.title)
+            )
+        })
+        .collect()
+}
+
+/// The `[pause 3m12s]` marker before a turn, if the silence before it is longer than
+/// the threshold.
+fn pause_marker(
+    segments: &[SpeakerSegment],
+    index: usize,
+    threshold: Option<u64>,
+) -> Option<String> {
+    let pause = pause_before(segments, index)?;
+    (pause > threshold?).then(|| format!("[pause {}]", format_duration(pause)))
+}
+
+/// The `<a id>` anchor for a turn, or an empty string without anchors.
+fn anchor_tag(anchors: &[String], index: usize) -> String {
+    anchors
+        .get(index)
+        .map(|id| format!("<a id=\"{}\"></a>", id))
+        .unwrap_or_default()
+}
+
+/// A `[time]` label, linked into the recording when there is a media link.
+fn timestamp_label(label: &str, timestamp: &str, media_link: Option<&MediaLink>) -> String {
+    match media_link.and_then(|link| link.at(timestamp)) {
+        Some(url) => format!("[{}]({})", label, url),
+        None => format!("[{}]", label),
+    }
+}
+
+/// The segment text with inline cue timestamps under `TimestampMode::Each` (and
+/// paragraph timestamps under `First`), linked into the recording when there is a media
+/// link, and `paragraph_break` between paragraphs.
+fn inline_text(
+    segment: &SpeakerSegment,
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+    media_link: Option<&MediaLink>,
+    paragraph_break: &str,
+) -> String {
+    segment
+        .paragraphs(timestamp_mode)
+        .into_iter()
+        .map(|parts| {
+            parts
+                .into_iter()
+                .map(|(timestamp, text)| match timestamp {
+                    Some(timestamp) => format!(
+                        "{} {}",
+                        timestamp_label(
+                            &timestamp_format.display(timestamp),
+                            timestamp,
+                            media_link
+                        ),
+                        text
+                    ),
+                    None => text.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join(paragraph_break)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
+    use crate::consolidator::SegmentCue;
+    use crate::ics::CalendarEvent;
 
     #[test]
     fn test_format_markdown_no_timestamps() {
@@ -170,16 +558,27 @@ mod tests {
                 text: "Hello world.".to_string(),
                 timestamp: None,
                 timestamps: vec![],
+                end_timestamp: None,
+                cues: Vec::new(),
             },
             SpeakerSegment {
                 speaker: "Bob".to_string(),
                 text: "Hi Alice!".to_string(),
                 timestamp: None,
                 timestamps: vec![],
+                end_timestamp: None,
+                cues: Vec::new(),
             },
         ];
 
-        let markdown = format_markdown(&segments, TimestampMode::None);
+        let markdown = format_markdown(
+            &segments,
+            TimestampMode::None,
+            &TimestampFormat::default(),
+            None,
+            &[],
+            &MarkdownLayout::default(),
+        );
 
         assert_eq!(
             markdown,
@@ -195,16 +594,27 @@ mod tests {
                 text: "Hello world.".to_string(),
                 timestamp: Some("00:00:01.000".to_string()),
                 timestamps: vec![],
+                end_timestamp: None,
+                cues: Vec::new(),
             },
             SpeakerSegment {
                 speaker: "Bob".to_string(),
                 text: "Hi Alice!".to_string(),
                 timestamp: Some("00:00:05.000".to_string()),
                 timestamps: vec![],
+                end_timestamp: None,
+                cues: Vec::new(),
             },
         ];
 
-        let markdown = format_markdown(&segments, TimestampMode::First);
+        let markdown = format_markdown(
+            &segments,
+            TimestampMode::First,
+            &TimestampFormat::default(),
+            None,
+            &[],
+            &MarkdownLayout::default(),
+        );
 
         assert_eq!(
             markdown,
@@ -219,88 +629,525 @@ mod tests {
             text: "Hello world. How are you?".to_string(),
             timestamp: None,
             timestamps: vec!["00:00:01.000".to_string(), "00:00:02.000".to_string()],
+            end_timestamp: None,
+            cues: Vec::new(),
         }];
 
-        let markdown = format_markdown(&segments, TimestampMode::Each);
+        let markdown = format_markdown(
+            &segments,
+            TimestampMode::Each,
+            &TimestampFormat::default(),
+            None,
+            &[],
+            &MarkdownLayout::default(),
+        );
 
-        // For now, Each mode shows first timestamp with full text
+        // Without cue boundaries, the turn's first timestamp leads the full text
         assert_eq!(
             markdown,
             "[00:00:01.000] **Alice:** Hello world. How are you?\n\n"
         );
+
+        let segments = vec![SpeakerSegment {
+            cues: vec![
+                SegmentCue {
+                    timestamp: Some("00:00:01.000".to_string()),
+                    end_timestamp: None,
+                    text: "Hello world.".to_string(),
+                    new_paragraph: false,
+                },
+                SegmentCue {
+                    timestamp: Some("00:00:02.000".to_string()),
+                    end_timestamp: None,
+                    text: "How are you?".to_string(),
+                    new_paragraph: false,
+                },
+            ],
+            ..segments[0].clone()
+        }];
+        let markdown = format_markdown(
+            &segments,
+            TimestampMode::Each,
+            &TimestampFormat::default(),
+            None,
+            &[],
+            &MarkdownLayout::default(),
+        );
+
+        // Every later cue gets an inline timestamp
+        assert_eq!(
+            markdown,
+            "[00:00:01.000] **Alice:** Hello world. [00:00:02.000] How are you?\n\n"
+        );
+    }
+
+    fn two_turns() -> Vec<SpeakerSegment> {
+        vec![
+            SpeakerSegment {
+                speaker: "Alice".to_string(),
+                text: "Hello world.".to_string(),
+                timestamp: Some("00:00:01.000".to_string()),
+                timestamps: vec!["00:00:01.000".to_string()],
+                end_timestamp: None,
+                cues: Vec::new(),
+            },
+            SpeakerSegment {
+                speaker: "Bob".to_string(),
+                text: "Yes | no?".to_string(),
+                timestamp: Some("00:00:05.000".to_string()),
+                timestamps: vec!["00:00:05.000".to_string()],
+                end_timestamp: None,
+                cues: Vec::new(),
+            },
+        ]
+    }
+
+    fn style(style: MarkdownStyle) -> MarkdownLayout {
+        MarkdownLayout {
+            style,
+            ..MarkdownLayout::default()
+        }
     }
 
     #[test]
-    fn test_write_markdown_file_success() {
-        let temp_file = std::env::temp_dir().join("test_write_success.md");
-        let content = "**Alice:** Hello world.\n\n";
+    fn test_format_markdown_styles() {
+        let segments = two_turns();
 
-        // Clean up any existing file
-        fs::remove_file(&temp_file).ok();
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::First,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &style(MarkdownStyle::Heading)
+            ),
+            "### [00:00:01.000] Alice\n\nHello world.\n\n### [00:00:05.000] Bob\n\nYes | no?\n\n"
+        );
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::None,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &style(MarkdownStyle::Blockquote)
+            ),
+            "> **Alice:** Hello world.\n\n> **Bob:** Yes | no?\n\n"
+        );
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::None,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &style(MarkdownStyle::Definition)
+            ),
+            "Alice\n:   Hello world.\n\nBob\n:   Yes | no?\n\n"
+        );
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::First,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &style(MarkdownStyle::Chat)
+            ),
+            "- [00:00:01.000] **Alice:** Hello world.\n\n- [00:00:05.000] **Bob:** Yes | no?\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_markdown_table() {
+        let segments = two_turns();
+
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::None,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &style(MarkdownStyle::Table)
+            ),
+            "| Speaker | Text |\n| --- | --- |\n\
+             | Alice | Hello world. |\n\
+             | Bob | Yes \\| no? |\n\n"
+        );
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::First,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &style(MarkdownStyle::Table)
+            ),
+            "| Time | Speaker | Text |\n| --- | --- | --- |\n\
+             | 00:00:01.000 | Alice | Hello world. |\n\
+             | 00:00:05.000 | Bob | Yes \\| no? |\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_markdown_separator_and_compact() {
+        let segments = two_turns();
+        let layout = MarkdownLayout {
+            style: MarkdownStyle::Bold,
+            separator: true,
+            ..MarkdownLayout::default()
+        };
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::None,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &layout
+            ),
+            "**Alice:** Hello world.\n\n***\n\n**Bob:** Yes | no?\n\n"
+        );
+
+        let layout = MarkdownLayout {
+            compact: true,
+            ..layout
+        };
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::None,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &layout
+            ),
+            "**Alice:** Hello world.\n***\n**Bob:** Yes | no?\n"
+        );
 
-        let result = write_markdown_file(content, &temp_file, false, false);
-        assert!(result.is_ok());
+        let layout = MarkdownLayout {
+            style: MarkdownStyle::Chat,
+            compact: true,
+            ..MarkdownLayout::default()
+        };
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::None,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &layout
+            ),
+            "- **Alice:** Hello world.\n- **Bob:** Yes | no?\n"
+        );
+    }
 
-        // Verify content
-        let written = fs::read_to_string(&temp_file).unwrap();
-        assert_eq!(written, content);
+    #[test]
+    fn test_format_markdown_anchors_and_index() {
+        let segments = two_turns();
+        let layout = MarkdownLayout {
+            index: true,
+            ..MarkdownLayout::default()
+        };
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::First,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &layout
+            ),
+            "## Index\n\n\
+             - [00:00:01.000 Alice](#t-00-00-01-alice)\n\
+             - [00:00:05.000 Bob](#t-00-00-05-bob)\n\n\
+             <a id=\"t-00-00-01-alice\"></a>[00:00:01.000] **Alice:** Hello world.\n\n\
+             <a id=\"t-00-00-05-bob\"></a>[00:00:05.000] **Bob:** Yes | no?\n\n"
+        );
 
-        // Clean up
-        fs::remove_file(&temp_file).ok();
+        // Table rows carry the anchor in their first cell
+        let layout = MarkdownLayout {
+            style: MarkdownStyle::Table,
+            anchors: true,
+            ..MarkdownLayout::default()
+        };
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::None,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &layout
+            ),
+            "| Speaker | Text |\n| --- | --- |\n\
+             | <a id=\"t-00-00-01-alice\"></a>Alice | Hello world. |\n\
+             | <a id=\"t-00-00-05-bob\"></a>Bob | Yes \\| no? |\n\n"
+        );
     }
 
     #[test]
-    fn test_write_markdown_file_exists_no_force() {
-        let temp_file = std::env::temp_dir().join("test_write_exists.md");
+    fn test_format_markdown_paragraphs() {
+        let cue = |timestamp: &str, text: &str, new_paragraph: bool| SegmentCue {
+            timestamp: Some(timestamp.to_string()),
+            end_timestamp: None,
+            text: text.to_string(),
+            new_paragraph,
+        };
+        let segments = vec![SpeakerSegment {
+            speaker: "Alice".to_string(),
+            text: "Welcome.\n\nFirst, the launch.".to_string(),
+            timestamp: Some("00:00:01.000".to_string()),
+            timestamps: vec!["00:00:01.000".to_string(), "00:00:05.000".to_string()],
+            end_timestamp: None,
+            cues: vec![
+                cue("00:00:01.000", "Welcome.", false),
+                cue("00:00:05.000", "First, the launch.", true),
+            ],
+        }];
+        let render = |timestamp_mode, style| {
+            format_markdown(
+                &segments,
+                timestamp_mode,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &MarkdownLayout {
+                    style,
+                    ..MarkdownLayout::default()
+                },
+            )
+        };
 
-        // Create existing file
-        fs::write(&temp_file, "existing content").unwrap();
+        // The speaker label is printed once, and continuation paragraphs get their time
+        assert_eq!(
+            render(TimestampMode::First, MarkdownStyle::Bold),
+            "[00:00:01.000] **Alice:** Welcome.\n\n[00:00:05.000] First, the launch.\n\n"
+        );
+        assert_eq!(
+            render(TimestampMode::None, MarkdownStyle::Blockquote),
+            "> **Alice:** Welcome.\n>\n> First, the launch.\n\n"
+        );
+        assert_eq!(
+            render(TimestampMode::None, MarkdownStyle::Chat),
+            "- **Alice:** Welcome.\n\n  First, the launch.\n\n"
+        );
+        assert_eq!(
+            render(TimestampMode::None, MarkdownStyle::Table),
+            "| Speaker | Text |\n| --- | --- |\n\
+             | Alice | Welcome.<br><br>First, the launch. |\n\n"
+        );
+    }
 
-        let result = write_markdown_file("new content", &temp_file, false, false);
+    #[test]
+    fn test_format_markdown_pause_markers() {
+        let mut segments = two_turns();
+        segments[0].end_timestamp = Some("00:00:02.000".to_string());
+        segments[1].timestamps = vec!["00:03:14.000".to_string()];
+        let layout = MarkdownLayout {
+            pause_markers: Some(60_000),
+            ..MarkdownLayout::default()
+        };
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::None,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &layout
+            ),
+            "**Alice:** Hello world.\n\n[pause 3m12s]\n\n**Bob:** Yes | no?\n\n"
+        );
 
-        assert!(result.is_err());
-        match result {
-            Err(VttError::OutputExists { .. }) => {}
-            _ => panic!("Expected OutputExists error"),
-        }
+        let layout = MarkdownLayout {
+            style: MarkdownStyle::Table,
+            ..layout
+        };
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::None,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &layout
+            ),
+            "| Speaker | Text |\n| --- | --- |\n\
+             | Alice | Hello world. |\n\
+             | | [pause 3m12s] |\n\
+             | Bob | Yes \\| no? |\n\n"
+        );
 
-        // Clean up
-        fs::remove_file(&temp_file).ok();
+        // Shorter silences are not marked
+        let layout = MarkdownLayout {
+            pause_markers: Some(600_000),
+            ..MarkdownLayout::default()
+        };
+        assert!(
+            !format_markdown(
+                &segments,
+                TimestampMode::None,
+                &TimestampFormat::default(),
+                None,
+                &[],
+                &layout
+            )
+            .contains("pause")
+        );
     }
 
     #[test]
-    fn test_write_markdown_file_exists_with_force() {
-        let temp_file = std::env::temp_dir().join("test_write_force.md");
+    fn test_format_markdown_chapters() {
+        let segments = two_turns();
+        let chapters = vec![
+            Chapter {
+                title: "Intro".to_string(),
+                start: "00:00:00.000".to_string(),
+                end: None,
+                id: "chapter-intro".to_string(),
+            },
+            Chapter {
+                title: "Q&A".to_string(),
+                start: "00:00:03.000".to_string(),
+                end: None,
+                id: "chapter-q-a".to_string(),
+            },
+        ];
+        let layout = MarkdownLayout {
+            separator: true,
+            toc: true,
+            ..MarkdownLayout::default()
+        };
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::None,
+                &TimestampFormat::default(),
+                None,
+                &chapters,
+                &layout
+            ),
+            "## Contents\n\n\
+             - [Intro](#chapter-intro)\n\
+             - [Q&A](#chapter-q-a)\n\n\
+             ## <a id=\"chapter-intro\"></a>Intro\n\n\
+             **Alice:** Hello world.\n\n\
+             ## <a id=\"chapter-q-a\"></a>Q&A\n\n\
+             **Bob:** Yes | no?\n\n"
+        );
+
+        // Each chapter starts a new table
+        assert_eq!(
+            format_markdown(
+                &segments,
+                TimestampMode::None,
+                &TimestampFormat::default(),
+                None,
+                &chapters[1..],
+                &style(MarkdownStyle::Table)
+            ),
+            "| Speaker | Text |\n| --- | --- |\n\
+             | Alice | Hello world. |\n\n\
+             ## <a id=\"chapter-q-a\"></a>Q&A\n\n\
+             | Speaker | Text |\n| --- | --- |\n\
+             | Bob | Yes \\| no? |\n\n"
+        );
+    }
 
-        // Create existing file
-        fs::write(&temp_file, "existing content").unwrap();
+    #[test]
+    fn test_format_meeting_header() {
+        assert_eq!(
+            format_meeting_header(&DocumentMetadata::default(), &two_turns()),
+            ""
+        );
 
-        let result = write_markdown_file("new content", &temp_file, true, false);
-        assert!(result.is_ok());
+        let metadata = DocumentMetadata {
+            title: Some("Weekly sync".to_string()),
+            meeting: Some(CalendarEvent {
+                summary: Some("Weekly sync".to_string()),
+                start: Some("2025-11-18T15:00:00".to_string()),
+                time_zone: Some("Europe/Berlin".to_string()),
+                organizer: Some("Jane Doe".to_string()),
+                attendees: vec![
+                    "Alice".to_string(),
+                    "bob".to_string(),
+                    "Carol_C".to_string(),
+                ],
+            }),
+            ..DocumentMetadata::default()
+        };
 
-        // Verify content was overwritten
-        let written = fs::read_to_string(&temp_file).unwrap();
-        assert_eq!(written, "new content");
+        assert_eq!(
+            format_meeting_header(&metadata, &two_turns()),
+            "# Weekly sync\n\n\
+             - **When:** 2025-11-18 15:00:00 (Europe/Berlin)\n\
+             - **Organizer:** Jane Doe\n\
+             - **Attendees:** Alice, bob, Carol\\_C\n\
+             - **Did not speak:** Carol\\_C\n\n"
+        );
+    }
 
-        // Clean up
-        fs::remove_file(&temp_file).ok();
+    fn interview() -> Vec<SpeakerSegment> {
+        ["Host", "Guest\\_One", "Host", "Guest Two"]
+            .iter()
+            .enumerate()
+            .map(|(index, speaker)| SpeakerSegment {
+                speaker: speaker.to_string(),
+                text: format!("Turn {}.", index + 1),
+                timestamp: Some(format!("00:00:0{}.000", index)),
+                timestamps: vec![format!("00:00:0{}.000", index)],
+                end_timestamp: None,
+                cues: Vec::new(),
+            })
+            .collect()
     }
 
     #[test]
-    fn test_write_markdown_file_no_clobber() {
-        let temp_file = std::env::temp_dir().join("test_write_no_clobber.md");
+    fn test_format_qa() {
+        let markdown = format_qa(
+            &interview(),
+            TimestampMode::None,
+            &TimestampFormat::default(),
+            None,
+            &["host".to_string()],
+        );
 
-        // Create existing file
-        fs::write(&temp_file, "existing content").unwrap();
+        assert_eq!(
+            markdown,
+            "**Q:** Turn 1.\n\n**A:** Turn 2.\n\n**Q:** Turn 3.\n\n**A:** Turn 4.\n\n"
+        );
+    }
 
-        let result = write_markdown_file("new content", &temp_file, false, true);
-        assert!(result.is_ok()); // Should succeed but not write
+    #[test]
+    fn test_format_qa_interviewer_names() {
+        // Names are compared without Markdown escaping; several interviewers are allowed
+        let interviewers = vec!["Host".to_string(), "Guest_One".to_string()];
+        let markdown = format_qa(
+            &interview(),
+            TimestampMode::First,
+            &TimestampFormat::default(),
+            None,
+            &interviewers,
+        );
 
-        // Verify content was NOT overwritten
-        let written = fs::read_to_string(&temp_file).unwrap();
-        assert_eq!(written, "existing content");
+        assert_eq!(
+            markdown,
+            "[00:00:00.000] **Q:** Turn 1.\n\n[00:00:01.000] **Q:** Turn 2.\n\n\
+             [00:00:02.000] **Q:** Turn 3.\n\n[00:00:03.000] **A:** Turn 4.\n\n"
+        );
 
-        // Clean up
-        fs::remove_file(&temp_file).ok();
+        // Without a configured interviewer, the first speaker asks the questions
+        let markdown = format_qa(
+            &interview(),
+            TimestampMode::None,
+            &TimestampFormat::default(),
+            None,
+            &[],
+        );
+        assert!(markdown.starts_with("**Q:** Turn 1.\n\n**A:** Turn 2.\n\n**Q:** Turn 3."));
     }
 }
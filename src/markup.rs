@@ -0,0 +1,338 @@
+//! Org-mode, AsciiDoc and reStructuredText generation.
+//!
+//! These renderers use the same layout as the Markdown output: one paragraph per
+//! speaker turn with the speaker name in bold and an optional leading timestamp. Each
+//! format gets a title from the document metadata and its own escaping rules for
+//! speaker names and spoken text, since the parser's escaping only suits Markdown.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let org = format_org(
+//!     &segments,
+//!     &metadata,
+//!     TimestampMode::First,
+//!     &TimestampFormat::default(),
+//! );
+//! // "#+TITLE: Weekly sync\n\n[00:00:01.000] *Alice:* Hello.\n\n"
+//! ```
+
+use crate::cli::TimestampMode;
+use crate::consolidator::SpeakerSegment;
+use crate::parser::{DocumentMetadata, unescape_markdown};
+use crate::timestamp::TimestampFormat;
+
+/// Zero-width space, used to stop Org from treating a character as markup.
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+
+/// Format speaker segments as an Org-mode document.
+///
+/// Turns are written as `[HH:MM:SS.mmm] *Speaker:* text`, with the timestamp only
+/// when the mode includes one.
+///
+/// # Arguments
+///
+/// * `segments` - The consolidated speaker segments to format
+/// * `metadata` - Document metadata, used for the `#+TITLE:` keyword
+/// * `timestamp_mode` - How to include timestamps (None, First, or Each)
+/// * `timestamp_format` - How to display timestamps
+pub fn format_org(
+    segments: &[SpeakerSegment],
+    metadata: &DocumentMetadata,
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+) -> String {
+    let mut result = format!("#+TITLE: {}\n\n", escape_org(&metadata.display_title()));
+    result.push_str(&format_turns(
+        segments,
+        timestamp_mode,
+        timestamp_format,
+        |speaker| format!("*{}:*", escape_org(speaker)),
+        escape_org,
+    ));
+    result
+}
+
+/// Format speaker segments as an AsciiDoc document.
+///
+/// Turns are written as `[HH:MM:SS.mmm] *Speaker:* text` below a `= Title` header.
+///
+/// # Arguments
+///
+/// * `segments` - The consolidated speaker segments to format
+/// * `metadata` - Document metadata, used for the document title
+/// * `timestamp_mode` - How to include timestamps (None, First, or Each)
+/// * `timestamp_format` - How to display timestamps
+pub fn format_asciidoc(
+    segments: &[SpeakerSegment],
+    metadata: &DocumentMetadata,
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+) -> String {
+    let mut result = format!("= {}\n\n", escape_asciidoc(&metadata.display_title()));
+    result.push_str(&format_turns(
+        segments,
+        timestamp_mode,
+        timestamp_format,
+        |speaker| format!("*{}:*", escape_asciidoc(speaker)),
+        escape_asciidoc,
+    ));
+    result
+}
+
+/// Format speaker segments as a reStructuredText document.
+///
+/// Turns are written as `[HH:MM:SS.mmm] **Speaker:** text` below an underlined title.
+///
+/// # Arguments
+///
+/// * `segments` - The consolidated speaker segments to format
+/// * `metadata` - Document metadata, used for the document title
+/// * `timestamp_mode` - How to include timestamps (None, First, or Each)
+/// * `timestamp_format` - How to display timestamps
+pub fn format_rst(
+    segments: &[SpeakerSegment],
+    metadata: &DocumentMetadata,
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+) -> String {
+    let title = escape_rst(&metadata.display_title());
+    // Wide characters take two columns, and a short underline is an error
+    let width: usize = title
+        .chars()
+        .map(|c| if c.is_ascii() { 1 } else { 2 })
+        .sum();
+    let mut result = format!("{}\n{}\n\n", title, "=".repeat(width));
+    result.push_str(&format_turns(
+        segments,
+        timestamp_mode,
+        timestamp_format,
+        |speaker| format!("**{}:**", escape_rst(speaker)),
+        escape_rst,
+    ));
+    result
+}
+
+/// Lay out speaker turns, separated by blank lines.
+///
+/// `label` turns the (unescaped) speaker name into the format's bold label, and
+/// `escape` escapes the spoken text.
+fn format_turns<F, E>(
+    segments: &[SpeakerSegment],
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+    label: F,
+    escape: E,
+) -> String
+where
+    F: Fn(&str) -> String,
+    E: Fn(&str) -> String,
+{
+    let mut result = String::new();
+
+    let labels = timestamp_format.turn_labels(segments, timestamp_mode);
+    for (segment, timestamp) in segments.iter().zip(&labels) {
+        if let Some(timestamp) = timestamp {
+            result.push_str(&format!("[{}] ", timestamp));
+        }
+        result.push_str(&label(&unescape_markdown(&segment.speaker)));
+        result.push(' ');
+        result.push_str(&timestamp_format.inline_text(segment, timestamp_mode, &escape, "\n\n"));
+        result.push_str("\n\n");
+    }
+
+    result
+}
+
+/// Escape text for Org-mode.
+///
+/// Org has no escape character, so a zero-width space is inserted after each
+/// emphasis marker to keep it from opening or closing markup.
+fn escape_org(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        result.push(ch);
+        if matches!(ch, '*' | '/' | '_' | '=' | '~' | '+' | '[' | ']') {
+            result.push(ZERO_WIDTH_SPACE);
+        }
+    }
+
+    result
+}
+
+/// Escape AsciiDoc inline markup and attribute references with backslashes.
+fn escape_asciidoc(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        if matches!(ch, '*' | '_' | '`' | '#' | '^' | '~' | '+' | '{' | '[') {
+            result.push('\\');
+        }
+        result.push(ch);
+    }
+
+    result
+}
+
+/// Escape reStructuredText inline markup with backslashes.
+fn escape_rst(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '*' | '`' | '_' | '|' | '[' | ']') {
+            result.push('\\');
+        }
+        result.push(ch);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments() -> Vec<SpeakerSegment> {
+        vec![
+            SpeakerSegment {
+                speaker: "Alice".to_string(),
+                text: "Hello everyone.".to_string(),
+                timestamp: Some("00:00:01.000".to_string()),
+                timestamps: vec!["00:00:01.000".to_string()],
+                end_timestamp: None,
+                cues: Vec::new(),
+            },
+            SpeakerSegment {
+                speaker: "John\\*Doe".to_string(),
+                text: "Hi.".to_string(),
+                timestamp: Some("00:00:05.000".to_string()),
+                timestamps: vec!["00:00:05.000".to_string()],
+                end_timestamp: None,
+                cues: Vec::new(),
+            },
+        ]
+    }
+
+    fn metadata() -> DocumentMetadata {
+        DocumentMetadata {
+            title: Some("Weekly sync".to_string()),
+            ..DocumentMetadata::default()
+        }
+    }
+
+    #[test]
+    fn test_format_org() {
+        assert_eq!(
+            format_org(
+                &segments(),
+                &metadata(),
+                TimestampMode::First,
+                &TimestampFormat::default()
+            ),
+            "#+TITLE: Weekly sync\n\n\
+             [00:00:01.000] *Alice:* Hello everyone.\n\n\
+             [00:00:05.000] *John*\u{200B}Doe:* Hi.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_asciidoc() {
+        assert_eq!(
+            format_asciidoc(
+                &segments(),
+                &metadata(),
+                TimestampMode::None,
+                &TimestampFormat::default()
+            ),
+            "= Weekly sync\n\n*Alice:* Hello everyone.\n\n*John\\*Doe:* Hi.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_rst() {
+        assert_eq!(
+            format_rst(
+                &segments(),
+                &metadata(),
+                TimestampMode::Each,
+                &TimestampFormat::default()
+            ),
+            "Weekly sync\n===========\n\n\
+             [00:00:01.000] **Alice:** Hello everyone.\n\n\
+             [00:00:05.000] **John\\*Doe:** Hi.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_escaping_rules() {
+        assert_eq!(escape_org("a_b/c"), "a_\u{200B}b/\u{200B}c");
+        assert_eq!(escape_asciidoc("{name} #1"), "\\{name} \\#1");
+        assert_eq!(escape_rst("a_b|c\\d"), "a\\_b\\|c\\\\d");
+        // Characters with no special meaning are left alone
+        assert_eq!(escape_org("Dr. O'Brien"), "Dr. O'Brien");
+        assert_eq!(escape_asciidoc("Dr. O'Brien"), "Dr. O'Brien");
+        assert_eq!(escape_rst("Dr. O'Brien"), "Dr. O'Brien");
+    }
+
+    fn markup_segment(text: &str) -> Vec<SpeakerSegment> {
+        vec![SpeakerSegment {
+            text: text.to_string(),
+            ..segments().remove(0)
+        }]
+    }
+
+    #[test]
+    fn test_format_org_escapes_text() {
+        let org = format_org(
+            &markup_segment("This is *important* [sic]."),
+            &metadata(),
+            TimestampMode::None,
+            &TimestampFormat::default(),
+        );
+        assert!(
+            org.ends_with(
+                "*Alice:* This is *\u{200B}important*\u{200B} [\u{200B}sic]\u{200B}.\n\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_asciidoc_escapes_text() {
+        let asciidoc = format_asciidoc(
+            &markup_segment("Set {attr} to *on* in `config`."),
+            &metadata(),
+            TimestampMode::None,
+            &TimestampFormat::default(),
+        );
+        assert!(asciidoc.ends_with("*Alice:* Set \\{attr} to \\*on\\* in \\`config\\`.\n\n"));
+    }
+
+    #[test]
+    fn test_format_rst_escapes_text() {
+        let rst = format_rst(
+            &markup_segment("Run `x` with *care*_ now."),
+            &metadata(),
+            TimestampMode::None,
+            &TimestampFormat::default(),
+        );
+        assert!(rst.ends_with("**Alice:** Run \\`x\\` with \\*care\\*\\_ now.\n\n"));
+    }
+
+    #[test]
+    fn test_format_rst_wide_title_underline() {
+        let metadata = DocumentMetadata {
+            title: Some("会議".to_string()),
+            ..DocumentMetadata::default()
+        };
+        assert!(
+            format_rst(
+                &[],
+                &metadata,
+                TimestampMode::None,
+                &TimestampFormat::default()
+            )
+            .starts_with("会議\n====\n\n")
+        );
+    }
+}
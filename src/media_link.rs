@@ -0,0 +1,196 @@
+//! Deep links from timestamps into the meeting recording.
+//!
+//! With `--media-url`, Markdown timestamps become links that open the recording at
+//! that moment. Players expect the start time in different places, so the link style
+//! is chosen per platform:
+//!
+//! - `youtube` - `?t=754` query parameter
+//! - `vimeo` - `#t=754s` fragment
+//! - `stream` - `?st=754` query parameter (Microsoft Stream)
+//! - `query` - `?t=754` query parameter, for other players that read one
+//! - `fragment` - `#t=00:12:34` [media fragment](https://www.w3.org/TR/media-frags/),
+//!   understood by browsers playing a media file directly
+//!
+//! Without `--link-style`, the style is detected from the URL's host, falling back to
+//! a media fragment. Links point at the time in the recording, so display options such
+//! as `--timestamp-offset` or `--start-time` don't change them.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let link = MediaLink::new("https://youtu.be/abc123", None);
+//! assert_eq!(link.at("00:12:34.500").as_deref(), Some("https://youtu.be/abc123?t=754"));
+//! ```
+
+use crate::cli::LinkStyle;
+use crate::timestamp::parse_timestamp;
+
+/// A recording URL and how to point it at a moment in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaLink {
+    url: String,
+    style: LinkStyle,
+}
+
+impl MediaLink {
+    /// Create a link builder for a recording URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the recording
+    /// * `style` - How to add the start time, or `None` to detect it from the host
+    pub fn new(url: &str, style: Option<LinkStyle>) -> MediaLink {
+        MediaLink {
+            url: url.trim().to_string(),
+            style: style.unwrap_or_else(|| detect_style(url)),
+        }
+    }
+
+    /// The URL that opens the recording at a timestamp.
+    ///
+    /// Returns `None` if the timestamp can't be parsed.
+    pub fn at(&self, timestamp: &str) -> Option<String> {
+        let seconds = parse_timestamp(timestamp)? / 1000;
+
+        let url = match self.style {
+            LinkStyle::Youtube | LinkStyle::Query => {
+                with_query(&self.url, "t", &seconds.to_string())
+            }
+            LinkStyle::Stream => with_query(&self.url, "st", &seconds.to_string()),
+            LinkStyle::Vimeo => with_fragment(&self.url, &format!("t={}s", seconds)),
+            LinkStyle::Fragment => with_fragment(
+                &self.url,
+                &format!(
+                    "t={:02}:{:02}:{:02}",
+                    seconds / 3600,
+                    (seconds / 60) % 60,
+                    seconds % 60
+                ),
+            ),
+        };
+
+        // Keep the URL intact inside a Markdown link destination
+        Some(
+            url.replace(' ', "%20")
+                .replace('(', "%28")
+                .replace(')', "%29"),
+        )
+    }
+}
+
+/// Pick the link style from the URL's host.
+fn detect_style(url: &str) -> LinkStyle {
+    let after_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let host = after_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .rsplit('@')
+        .next()
+        .unwrap_or_default()
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let is = |domain: &str| host == domain || host.ends_with(&format!(".{}", domain));
+
+    if is("youtube.com") || is("youtu.be") {
+        LinkStyle::Youtube
+    } else if is("vimeo.com") {
+        LinkStyle::Vimeo
+    } else if is("microsoftstream.com") {
+        LinkStyle::Stream
+    } else {
+        LinkStyle::Fragment
+    }
+}
+
+/// Set a query parameter, replacing any existing value and keeping the fragment.
+fn with_query(url: &str, key: &str, value: &str) -> String {
+    let (base, fragment) = match url.split_once('#') {
+        Some((base, fragment)) => (base, format!("#{}", fragment)),
+        None => (url, String::new()),
+    };
+    let (path, query) = base.split_once('?').unwrap_or((base, ""));
+
+    let prefix = format!("{}=", key);
+    let mut params: Vec<&str> = query
+        .split('&')
+        .filter(|param| !param.is_empty() && !param.starts_with(&prefix))
+        .collect();
+    let param = format!("{}{}", prefix, value);
+    params.push(&param);
+
+    format!("{}?{}{}", path, params.join("&"), fragment)
+}
+
+/// Replace the URL's fragment.
+fn with_fragment(url: &str, fragment: &str) -> String {
+    let base = url.split_once('#').map_or(url, |(base, _)| base);
+    format!("{}#{}", base, fragment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_styles() {
+        let at = |url: &str, style: Option<LinkStyle>| {
+            MediaLink::new(url, style).at("00:12:34.500").unwrap()
+        };
+
+        assert_eq!(
+            at("https://www.youtube.com/watch?v=abc&t=10", None),
+            "https://www.youtube.com/watch?v=abc&t=754"
+        );
+        assert_eq!(
+            at("https://vimeo.com/123#t=5s", None),
+            "https://vimeo.com/123#t=754s"
+        );
+        assert_eq!(
+            at("https://web.microsoftstream.com/video/123", None),
+            "https://web.microsoftstream.com/video/123?st=754"
+        );
+        assert_eq!(
+            at("https://stream.example/video/123", None),
+            "https://stream.example/video/123#t=00:12:34"
+        );
+        assert_eq!(
+            at(
+                "https://stream.example/video/123#player",
+                Some(LinkStyle::Query)
+            ),
+            "https://stream.example/video/123?t=754#player"
+        );
+        // Spaces and parentheses would end a Markdown link
+        assert_eq!(
+            at("recordings/sync (1).mp4", None),
+            "recordings/sync%20%281%29.mp4#t=00:12:34"
+        );
+    }
+
+    #[test]
+    fn test_detect_style() {
+        assert_eq!(detect_style("https://youtu.be/abc"), LinkStyle::Youtube);
+        assert_eq!(
+            detect_style("https://m.youtube.com/watch?v=abc"),
+            LinkStyle::Youtube
+        );
+        assert_eq!(
+            detect_style("https://notyoutube.com/v"),
+            LinkStyle::Fragment
+        );
+        assert_eq!(
+            detect_style("https://user@vimeo.com:443/1"),
+            LinkStyle::Vimeo
+        );
+        assert_eq!(detect_style("meeting.mp4"), LinkStyle::Fragment);
+    }
+
+    #[test]
+    fn test_unparseable_timestamp() {
+        let link = MediaLink::new("https://youtu.be/abc", None);
+        assert_eq!(link.at("soon"), None);
+    }
+}
@@ -0,0 +1,267 @@
+//! Pandoc JSON AST output.
+//!
+//! Writing the transcript as a Pandoc document lets `pandoc` filters and writers take
+//! it from there (`pandoc -f json -t docx`, `-t epub`, ...). Each speaker turn is a
+//! `Para` holding an optional timestamp `Span` (class `timestamp`, with `start` and
+//! `end` attributes), the speaker name in `Strong`, and the consolidated text. The
+//! document title goes in the metadata.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let json = format_pandoc_json(
+//!     &segments,
+//!     &metadata,
+//!     TimestampMode::First,
+//!     &TimestampFormat::default(),
+//! )?;
+//! // pipe into: pandoc -f json -t html
+//! ```
+
+use crate::cli::TimestampMode;
+use crate::consolidator::SpeakerSegment;
+use crate::error::VttError;
+use crate::parser::{DocumentMetadata, unescape_markdown};
+use crate::timestamp::TimestampFormat;
+use serde_json::{Value, json};
+use std::io;
+
+/// Pandoc API version the AST is written against (pandoc 3.x).
+const PANDOC_API_VERSION: [u32; 3] = [1, 23, 1];
+
+/// Format speaker segments as a Pandoc JSON document.
+///
+/// # Arguments
+///
+/// * `segments` - The consolidated speaker segments to format
+/// * `metadata` - Document metadata, used for the `title` field
+/// * `timestamp_mode` - How to include timestamps (None, First, or Each)
+/// * `timestamp_format` - How to display timestamps
+///
+/// # Returns
+///
+/// A String containing the Pandoc AST as JSON, ending with a newline.
+pub fn format_pandoc_json(
+    segments: &[SpeakerSegment],
+    metadata: &DocumentMetadata,
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+) -> Result<String, VttError> {
+    let labels = timestamp_format.turn_labels(segments, timestamp_mode);
+    let blocks: Vec<Value> = segments
+        .iter()
+        .zip(&labels)
+        .flat_map(|(segment, label)| {
+            turn_paragraphs(segment, timestamp_mode, timestamp_format, label.as_deref())
+        })
+        .collect();
+
+    let document = json!({
+        "pandoc-api-version": PANDOC_API_VERSION,
+        "meta": {
+            "title": { "t": "MetaInlines", "c": text_inlines(&metadata.display_title()) }
+        },
+        "blocks": blocks,
+    });
+
+    let mut result = serde_json::to_string(&document).map_err(io::Error::from)?;
+    result.push('\n');
+    Ok(result)
+}
+
+/// Build the `Para` blocks for one speaker turn, the first with its displayed timestamp
+/// label and the speaker.
+///
+/// Under `TimestampMode::Each`, the start of every later cue is marked with its own
+/// timestamp `Span`, as is the start of every later paragraph under `First`.
+fn turn_paragraphs(
+    segment: &SpeakerSegment,
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+    label: Option<&str>,
+) -> Vec<Value> {
+    let mut inlines = Vec::new();
+
+    if let (Some(timestamp), Some(label)) = (segment.display_timestamp(timestamp_mode), label) {
+        let mut attributes = vec![json!(["start", timestamp])];
+        if let Some(ref end) = segment.end_timestamp {
+            attributes.push(json!(["end", end]));
+        }
+        inlines.push(json!({
+            "t": "Span",
+            "c": [["", ["timestamp"], attributes], text_inlines(&format!("[{}]", label))]
+        }));
+        inlines.push(json!({ "t": "Space" }));
+    }
+
+    let speaker = format!("{}:", unescape_markdown(&segment.speaker));
+    inlines.push(json!({ "t": "Strong", "c": text_inlines(&speaker) }));
+
+    let mut blocks = Vec::new();
+    for parts in segment.paragraphs(timestamp_mode) {
+        for (timestamp, text) in parts {
+            if let Some(timestamp) = timestamp {
+                let label = format!("[{}]", timestamp_format.display(timestamp));
+                if !inlines.is_empty() {
+                    inlines.push(json!({ "t": "Space" }));
+                }
+                inlines.push(json!({
+                    "t": "Span",
+                    "c": [["", ["timestamp"], [["start", timestamp]]], text_inlines(&label)]
+                }));
+            }
+            let text = text_inlines(text);
+            if !text.is_empty() {
+                if !inlines.is_empty() {
+                    inlines.push(json!({ "t": "Space" }));
+                }
+                inlines.extend(text);
+            }
+        }
+        blocks.push(json!({ "t": "Para", "c": std::mem::take(&mut inlines) }));
+    }
+
+    blocks
+}
+
+/// Split text into Pandoc `Str` inlines separated by `Space`.
+fn text_inlines(text: &str) -> Vec<Value> {
+    let mut inlines = Vec::new();
+
+    for word in text.split_whitespace() {
+        if !inlines.is_empty() {
+            inlines.push(json!({ "t": "Space" }));
+        }
+        inlines.push(json!({ "t": "Str", "c": word }));
+    }
+
+    inlines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consolidator::SegmentCue;
+
+    #[test]
+    fn test_text_inlines() {
+        assert_eq!(
+            Value::Array(text_inlines("Hello  big\nworld")),
+            json!([
+                { "t": "Str", "c": "Hello" },
+                { "t": "Space" },
+                { "t": "Str", "c": "big" },
+                { "t": "Space" },
+                { "t": "Str", "c": "world" }
+            ])
+        );
+        assert!(text_inlines("   ").is_empty());
+    }
+
+    #[test]
+    fn test_format_pandoc_json() {
+        let segments = vec![SpeakerSegment {
+            speaker: "John\\*Doe".to_string(),
+            text: "Hi all.".to_string(),
+            timestamp: Some("00:00:01.000".to_string()),
+            timestamps: vec!["00:00:01.000".to_string()],
+            end_timestamp: Some("00:00:03.500".to_string()),
+            cues: Vec::new(),
+        }];
+        let metadata = DocumentMetadata {
+            title: Some("Sync".to_string()),
+            ..DocumentMetadata::default()
+        };
+
+        let output = format_pandoc_json(
+            &segments,
+            &metadata,
+            TimestampMode::First,
+            &TimestampFormat::default(),
+        )
+        .unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(value["pandoc-api-version"], json!([1, 23, 1]));
+        assert_eq!(
+            value["meta"]["title"],
+            json!({ "t": "MetaInlines", "c": [{ "t": "Str", "c": "Sync" }] })
+        );
+        assert_eq!(
+            value["blocks"],
+            json!([{
+                "t": "Para",
+                "c": [
+                    {
+                        "t": "Span",
+                        "c": [
+                            ["", ["timestamp"], [["start", "00:00:01.000"], ["end", "00:00:03.500"]]],
+                            [{ "t": "Str", "c": "[00:00:01.000]" }]
+                        ]
+                    },
+                    { "t": "Space" },
+                    { "t": "Strong", "c": [{ "t": "Str", "c": "John*Doe:" }] },
+                    { "t": "Space" },
+                    { "t": "Str", "c": "Hi" },
+                    { "t": "Space" },
+                    { "t": "Str", "c": "all." }
+                ]
+            }])
+        );
+
+        // Without timestamps there is no Span
+        let output = format_pandoc_json(
+            &segments,
+            &metadata,
+            TimestampMode::None,
+            &TimestampFormat::default(),
+        )
+        .unwrap();
+        assert!(!output.contains("Span"));
+    }
+
+    #[test]
+    fn test_format_pandoc_json_inline_cue_timestamps() {
+        let cue = |timestamp: &str, text: &str| SegmentCue {
+            timestamp: Some(timestamp.to_string()),
+            end_timestamp: None,
+            text: text.to_string(),
+            new_paragraph: false,
+        };
+        let segments = vec![SpeakerSegment {
+            speaker: "Alice".to_string(),
+            text: "Hi. Bye.".to_string(),
+            timestamp: None,
+            timestamps: vec!["00:00:01.000".to_string(), "00:00:02.000".to_string()],
+            end_timestamp: None,
+            cues: vec![cue("00:00:01.000", "Hi."), cue("00:00:02.000", "Bye.")],
+        }];
+
+        let output = format_pandoc_json(
+            &segments,
+            &DocumentMetadata::default(),
+            TimestampMode::Each,
+            &TimestampFormat::default(),
+        )
+        .unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+
+        let inlines = value["blocks"][0]["c"].as_array().unwrap();
+        assert_eq!(
+            inlines[inlines.len() - 5..],
+            [
+                json!({ "t": "Str", "c": "Hi." }),
+                json!({ "t": "Space" }),
+                json!({
+                    "t": "Span",
+                    "c": [
+                        ["", ["timestamp"], [["start", "00:00:02.000"]]],
+                        [{ "t": "Str", "c": "[00:00:02.000]" }]
+                    ]
+                }),
+                json!({ "t": "Space" }),
+                json!({ "t": "Str", "c": "Bye." }),
+            ]
+        );
+    }
+}
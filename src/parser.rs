@@ -5,22 +5,32 @@
 //! It includes text sanitization, HTML entity decoding, and robust error handling for
 //! malformed VTT content.
 
+use crate::chapters::Chapter;
 use crate::error::VttError;
+use crate::ics::CalendarEvent;
 use regex::Regex;
+use serde::{Serialize, Serializer};
 use std::fs;
-use std::io::{self, BufRead, BufReader};
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 use unicode_normalization::UnicodeNormalization;
 
 /// Represents a single VTT cue with optional timestamp, speaker, and text content.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Cue {
     /// Optional timestamp for when this cue appears (format: HH:MM:SS.mmm)
     pub timestamp: Option<String>,
+    /// Optional timestamp for when this cue disappears (format: HH:MM:SS.mmm)
+    pub end_timestamp: Option<String>,
     /// Optional speaker name (extracted from <v> tags)
+    #[serde(serialize_with = "serialize_optional_speaker")]
     pub speaker: Option<String>,
     /// The text content of the cue
     pub text: String,
+    /// Optional cue identifier (the line before the timing line)
+    pub id: Option<String>,
+    /// Optional cue settings following the timing (e.g. "line:90% align:center")
+    pub settings: Option<String>,
 }
 
 /// Represents a parsed VTT document containing a collection of cues.
@@ -30,6 +40,45 @@ pub struct VttDocument {
     pub cues: Vec<Cue>,
     /// Whether this VTT file contains voice tags (Teams-style format)
     pub has_voice_tags: bool,
+    /// Information about the document as a whole
+    pub metadata: DocumentMetadata,
+}
+
+/// Document-level information gathered while reading an input.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DocumentMetadata {
+    /// Title text following the WEBVTT signature (e.g. `WEBVTT - Weekly sync`)
+    pub title: Option<String>,
+    /// Caption language, if the input records one
+    pub language: Option<String>,
+    /// Path of the input file
+    pub source: Option<PathBuf>,
+    /// Name of the input format that produced the document (e.g. "vtt", "hls")
+    pub format: Option<String>,
+    /// Meeting details from a calendar invite (`--ics`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meeting: Option<CalendarEvent>,
+    /// Chapters of the recording from a chapters track (`--chapters`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<Chapter>,
+}
+
+impl DocumentMetadata {
+    /// Title to show in rendered output.
+    ///
+    /// Uses the WEBVTT header title if present, then the input file name without
+    /// its extension, and finally a generic "Transcript".
+    pub fn display_title(&self) -> String {
+        self.title
+            .clone()
+            .or_else(|| {
+                self.source
+                    .as_deref()
+                    .and_then(Path::file_stem)
+                    .map(|stem| stem.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| "Transcript".to_string())
+    }
 }
 
 impl VttDocument {
@@ -57,23 +106,29 @@ impl VttDocument {
     pub fn parse<P: AsRef<Path>>(path: P) -> Result<Self, VttError> {
         let path = path.as_ref();
 
-        // Open and read the file
-        let file = fs::File::open(path).map_err(|e| {
-            if e.kind() == io::ErrorKind::NotFound {
-                VttError::FileNotFound {
-                    path: path.to_path_buf(),
-                }
-            } else if e.kind() == io::ErrorKind::PermissionDenied {
-                VttError::PermissionDenied {
-                    path: path.to_path_buf(),
-                }
-            } else {
-                VttError::IoError(e)
-            }
-        })?;
+        // Read the file
+        let content =
+            fs::read_to_string(path).map_err(|e| VttError::from_read_error(path, e))?;
+
+        Self::parse_str(&content)
+    }
 
-        let reader = BufReader::new(file);
-        let mut lines = reader.lines();
+    /// Parse VTT content that has already been loaded into memory.
+    ///
+    /// This is used for inputs that don't come from a standalone `.vtt` file, such as
+    /// HLS playlist segments. It applies the same validation and cue extraction as
+    /// [`VttDocument::parse`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let doc = VttDocument::parse_str("WEBVTT\n\n00:00:01.000 --> 00:00:02.000\nHello\n")?;
+    /// assert_eq!(doc.cues.len(), 1);
+    /// ```
+    pub fn parse_str(content: &str) -> Result<Self, VttError> {
+        // Strip a leading byte order mark if present
+        let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+        let mut lines = content.lines().map(|line| Ok(line.to_string()));
 
         // Validate WEBVTT header
         let first_line = lines
@@ -83,16 +138,36 @@ impl VttDocument {
             })?
             .map_err(VttError::IoError)?;
 
-        if !first_line.trim().starts_with("WEBVTT") {
+        let Some(header_text) = first_line.trim().strip_prefix("WEBVTT") else {
             return Err(VttError::ParseError {
                 reason: "Missing WEBVTT header".to_string(),
             });
-        }
+        };
 
         // Parse cues from the remaining lines
         let (cues, has_voice_tags) = parse_cues(lines)?;
 
-        Ok(VttDocument { cues, has_voice_tags })
+        Ok(VttDocument {
+            cues,
+            has_voice_tags,
+            metadata: DocumentMetadata {
+                title: parse_header_title(header_text),
+                ..DocumentMetadata::default()
+            },
+        })
+    }
+}
+
+/// Extract the title from the text after the WEBVTT signature.
+///
+/// The signature may be followed by a space or tab and free text; a leading dash
+/// separator (`WEBVTT - Title`) is dropped.
+fn parse_header_title(header_text: &str) -> Option<String> {
+    let title = header_text.trim().trim_start_matches('-').trim();
+    if title.is_empty() {
+        None
+    } else {
+        Some(title.to_string())
     }
 }
 
@@ -103,9 +178,14 @@ where
     I: Iterator<Item = io::Result<String>>,
 {
     let timestamp_regex =
-        Regex::new(r"^\s*(\d{2}:\d{2}:\d{2}\.\d{3})\s*-->\s*(\d{2}:\d{2}:\d{2}\.\d{3})").unwrap();
+        Regex::new(r"^\s*(\d{2}:\d{2}:\d{2}\.\d{3})\s*-->\s*(\d{2}:\d{2}:\d{2}\.\d{3})(.*)$")
+            .unwrap();
     let mut cues = Vec::new();
     let mut current_timestamp: Option<String> = None;
+    let mut current_end_timestamp: Option<String> = None;
+    let mut current_id: Option<String> = None;
+    let mut current_settings: Option<String> = None;
+    let mut pending_id: Option<String> = None;
     let mut current_text = Vec::new();
     let mut in_metadata_block = false;
 
@@ -126,12 +206,23 @@ where
         if let Some(captures) = timestamp_regex.captures(&line) {
             // Save any previous cue text
             if !current_text.is_empty() {
-                save_cue(&mut cues, current_timestamp.clone(), &current_text)?;
+                save_cue(
+                    &mut cues,
+                    current_timestamp.clone(),
+                    current_end_timestamp.clone(),
+                    current_id.take(),
+                    current_settings.take(),
+                    &current_text,
+                )?;
                 current_text.clear();
             }
 
             // Start new cue with timestamp
             current_timestamp = Some(captures[1].to_string());
+            current_end_timestamp = Some(captures[2].to_string());
+            current_id = pending_id.take();
+            let settings = captures[3].trim();
+            current_settings = (!settings.is_empty()).then(|| settings.to_string());
             in_metadata_block = false;
             continue;
         }
@@ -139,10 +230,19 @@ where
         // Empty line: end of cue or metadata block
         if trimmed.is_empty() {
             if !current_text.is_empty() {
-                save_cue(&mut cues, current_timestamp.clone(), &current_text)?;
+                save_cue(
+                    &mut cues,
+                    current_timestamp.clone(),
+                    current_end_timestamp.clone(),
+                    current_id.take(),
+                    current_settings.take(),
+                    &current_text,
+                )?;
                 current_text.clear();
                 current_timestamp = None;
+                current_end_timestamp = None;
             }
+            pending_id = None;
             in_metadata_block = false;
             continue;
         }
@@ -152,8 +252,9 @@ where
             continue;
         }
 
-        // Skip cue identifiers (lines before timestamp that are just numbers/IDs)
-        if current_timestamp.is_none() && !current_text.is_empty() {
+        // Remember cue identifiers (the line before a timestamp line)
+        if current_timestamp.is_none() {
+            pending_id = Some(trimmed.to_string());
             continue;
         }
 
@@ -165,7 +266,14 @@ where
 
     // Save final cue if any
     if !current_text.is_empty() {
-        save_cue(&mut cues, current_timestamp, &current_text)?;
+        save_cue(
+            &mut cues,
+            current_timestamp,
+            current_end_timestamp,
+            current_id,
+            current_settings,
+            &current_text,
+        )?;
     }
 
     // Check if any cues have speakers (indicating voice tags were present)
@@ -189,6 +297,9 @@ where
 fn save_cue(
     cues: &mut Vec<Cue>,
     timestamp: Option<String>,
+    end_timestamp: Option<String>,
+    id: Option<String>,
+    settings: Option<String>,
     text_lines: &[String],
 ) -> Result<(), VttError> {
     // Join lines and extract speaker
@@ -208,8 +319,11 @@ fn save_cue(
 
     cues.push(Cue {
         timestamp,
+        end_timestamp,
         speaker: sanitized_speaker,
         text: cleaned_text,
+        id,
+        settings,
     });
 
     Ok(())
@@ -308,8 +422,45 @@ fn sanitize_speaker_name(name: &str) -> Option<String> {
     Some(name)
 }
 
+/// Undo [`escape_markdown`] so non-Markdown renderers can show the original text.
+///
+/// Speaker names are Markdown-escaped at parse time; renderers for other formats
+/// call this before applying their own escaping.
+pub fn unescape_markdown(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            if let Some(next) = chars.next() {
+                result.push(next);
+            }
+        } else {
+            result.push(ch);
+        }
+    }
+
+    result
+}
+
+/// Serialize a speaker name without the Markdown escaping applied at parse time.
+pub fn serialize_speaker<S: Serializer>(speaker: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&unescape_markdown(speaker))
+}
+
+/// Serialize an optional speaker name without its Markdown escaping.
+fn serialize_optional_speaker<S: Serializer>(
+    speaker: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match speaker {
+        Some(speaker) => serialize_speaker(speaker, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 /// Escape Markdown special characters in text.
-fn escape_markdown(text: &str) -> String {
+pub fn escape_markdown(text: &str) -> String {
     let mut result = String::with_capacity(text.len());
 
     for ch in text.chars() {
@@ -420,6 +571,16 @@ mod tests {
         assert_eq!(escape_markdown("# heading"), "\\# heading");
     }
 
+    #[test]
+    fn test_unescape_markdown() {
+        assert_eq!(unescape_markdown("Normal text"), "Normal text");
+        assert_eq!(unescape_markdown("John\\*Doe"), "John*Doe");
+        assert_eq!(unescape_markdown("a\\\\b"), "a\\b");
+        for text in ["*bold* [x](y)", "back\\slash", "# heading!"] {
+            assert_eq!(unescape_markdown(&escape_markdown(text)), text);
+        }
+    }
+
     #[test]
     fn test_parse_valid_vtt_with_speakers() {
         let vtt_content = r#"WEBVTT
@@ -449,6 +610,7 @@ mod tests {
         assert_eq!(doc.cues[0].speaker, Some("Alice".to_string()));
         assert_eq!(doc.cues[0].text, "Hello, this is Alice speaking.");
         assert_eq!(doc.cues[0].timestamp, Some("00:00:01.000".to_string()));
+        assert_eq!(doc.cues[0].end_timestamp, Some("00:00:03.000".to_string()));
 
         assert_eq!(doc.cues[1].speaker, Some("Bob".to_string()));
         assert_eq!(doc.cues[1].text, "Hi Alice, this is Bob.");
@@ -459,6 +621,48 @@ mod tests {
         fs::remove_file(&temp_file).ok();
     }
 
+    #[test]
+    fn test_parse_header_title() {
+        assert_eq!(parse_header_title(""), None);
+        assert_eq!(parse_header_title("   "), None);
+        assert_eq!(parse_header_title(" - Weekly sync"), Some("Weekly sync".to_string()));
+        assert_eq!(parse_header_title("\tKickoff"), Some("Kickoff".to_string()));
+
+        let doc = VttDocument::parse_str("WEBVTT - Design review\n\n00:00:01.000 --> 00:00:02.000\nHi\n")
+            .unwrap();
+        assert_eq!(doc.metadata.title, Some("Design review".to_string()));
+    }
+
+    #[test]
+    fn test_parse_cue_ids_and_settings() {
+        let content = "WEBVTT\nKind: captions\n\n\
+                       intro-1\n00:00:01.000 --> 00:00:02.000 line:90% align:start\n<v Alice>Hi</v>\n\n\
+                       00:00:03.000 --> 00:00:04.000\n<v Bob>Hello</v>\n";
+        let doc = VttDocument::parse_str(content).unwrap();
+
+        assert_eq!(doc.cues.len(), 2);
+        assert_eq!(doc.cues[0].id, Some("intro-1".to_string()));
+        assert_eq!(
+            doc.cues[0].settings,
+            Some("line:90% align:start".to_string())
+        );
+        assert_eq!(doc.cues[0].text, "Hi");
+        assert_eq!(doc.cues[1].id, None);
+        assert_eq!(doc.cues[1].settings, None);
+    }
+
+    #[test]
+    fn test_display_title() {
+        let mut metadata = DocumentMetadata::default();
+        assert_eq!(metadata.display_title(), "Transcript");
+
+        metadata.source = Some(PathBuf::from("notes/Weekly Sync.vtt"));
+        assert_eq!(metadata.display_title(), "Weekly Sync");
+
+        metadata.title = Some("Design review".to_string());
+        assert_eq!(metadata.display_title(), "Design review");
+    }
+
     #[test]
     fn test_parse_missing_webvtt_header() {
         let vtt_content = r#"This is not a VTT file
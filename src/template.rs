@@ -0,0 +1,276 @@
+//! User-defined output templates.
+//!
+//! `--template FILE` renders the transcript with a [Handlebars](https://handlebarsjs.com)
+//! template instead of a built-in format, so teams can keep their own meeting-minute
+//! layouts. Templates are logic-light: they can insert values, loop with `{{#each}}`,
+//! and test with `{{#if}}`, but not compute. Values are inserted as-is, without HTML or
+//! Markdown escaping, and referring to a field that does not exist is an error.
+//!
+//! The template receives:
+//!
+//! - `title` - The document title (metadata title, or the input file name)
+//! - `metadata` - `title`, `language`, `source` and `format` of the input, the
+//!   `meeting` details from `--ics`, and the `chapters` from `--chapters` or `--topics`
+//! - `participants[]` - `name`, `turns` and `words` per speaker, in order of appearance
+//! - `segments[]` - `speaker`, `text` (with a blank line between paragraphs), `words`,
+//!   `start` and `end` of the turn, `timestamps` (start time of each cue),
+//!   `timestamp` (the time shown under `--include-timestamps`, or null), and `cues[]`
+//!   with the `start` and `text` of each cue in the turn
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // minutes.md.hbs:
+//! // # {{title}}
+//! // {{#each segments}}- **{{speaker}}** ({{start}}): {{text}}
+//! // {{/each}}
+//! let minutes = render_template(Path::new("minutes.md.hbs"), &transcript, mode, &format)?;
+//! ```
+
+use crate::cli::TimestampMode;
+use crate::consolidator::participants;
+use crate::error::VttError;
+use crate::output::Transcript;
+use crate::parser::{DocumentMetadata, unescape_markdown};
+use crate::timestamp::TimestampFormat;
+use handlebars::{Handlebars, no_escape};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// Everything a template can refer to.
+#[derive(Serialize)]
+struct TemplateData<'a> {
+    title: String,
+    metadata: &'a DocumentMetadata,
+    participants: Vec<ParticipantData>,
+    segments: Vec<SegmentData<'a>>,
+}
+
+#[derive(Serialize)]
+struct ParticipantData {
+    name: String,
+    turns: usize,
+    words: usize,
+}
+
+#[derive(Serialize)]
+struct SegmentData<'a> {
+    speaker: String,
+    text: &'a str,
+    words: usize,
+    start: Option<&'a str>,
+    end: Option<&'a str>,
+    timestamps: &'a [String],
+    timestamp: Option<String>,
+    cues: Vec<CueData<'a>>,
+}
+
+#[derive(Serialize)]
+struct CueData<'a> {
+    start: Option<&'a str>,
+    text: &'a str,
+}
+
+/// Render the transcript with a Handlebars template file.
+///
+/// # Arguments
+///
+/// * `path` - Path to the template file
+/// * `transcript` - The transcript to render
+/// * `timestamp_mode` - Decides the per-segment `timestamp` value
+/// * `timestamp_format` - How to display the per-segment `timestamp` value
+///
+/// # Returns
+///
+/// The rendered output.
+///
+/// # Errors
+///
+/// Returns `VttError::FileNotFound` or `VttError::PermissionDenied` if the template
+/// can't be read, and `VttError::TemplateError` with the line number if it fails to
+/// compile or render.
+pub fn render_template(
+    path: &Path,
+    transcript: &Transcript,
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+) -> Result<String, VttError> {
+    let source = fs::read_to_string(path).map_err(|e| VttError::from_read_error(path, e))?;
+
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(true);
+    handlebars.register_escape_fn(no_escape);
+    handlebars
+        .register_template_string("template", &source)
+        .map_err(|e| VttError::TemplateError {
+            path: path.to_path_buf(),
+            line: e.pos().map(|(line, _)| line),
+            reason: e.reason().to_string(),
+        })?;
+
+    handlebars
+        .render(
+            "template",
+            &template_data(transcript, timestamp_mode, timestamp_format),
+        )
+        .map_err(|e| VttError::TemplateError {
+            path: path.to_path_buf(),
+            line: e.line_no,
+            reason: e.reason().to_string(),
+        })
+}
+
+/// Build the template context from the transcript.
+fn template_data<'a>(
+    transcript: &Transcript<'a>,
+    timestamp_mode: TimestampMode,
+    timestamp_format: &TimestampFormat,
+) -> TemplateData<'a> {
+    let segments = transcript.segments;
+    let labels = timestamp_format.turn_labels(segments, timestamp_mode);
+
+    TemplateData {
+        title: transcript.metadata.display_title(),
+        metadata: transcript.metadata,
+        participants: participants(segments)
+            .into_iter()
+            .map(|participant| ParticipantData {
+                name: unescape_markdown(participant.speaker),
+                turns: participant.turns,
+                words: participant.words,
+            })
+            .collect(),
+        segments: segments
+            .iter()
+            .zip(labels)
+            .map(|(segment, label)| SegmentData {
+                speaker: unescape_markdown(&segment.speaker),
+                text: &segment.text,
+                words: segment.text.split_whitespace().count(),
+                start: segment.timestamps.first().map(String::as_str),
+                end: segment.end_timestamp.as_deref(),
+                timestamps: &segment.timestamps,
+                timestamp: label,
+                cues: segment
+                    .cues
+                    .iter()
+                    .map(|cue| CueData {
+                        start: cue.timestamp.as_deref(),
+                        text: &cue.text,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consolidator::{SegmentCue, SpeakerSegment};
+
+    fn render(name: &str, source: &str) -> Result<String, VttError> {
+        let segments = vec![
+            SpeakerSegment {
+                speaker: "John\\*Doe".to_string(),
+                text: "Let's begin.".to_string(),
+                timestamp: Some("00:00:01.000".to_string()),
+                timestamps: vec!["00:00:01.000".to_string()],
+                end_timestamp: Some("00:00:02.000".to_string()),
+                cues: vec![
+                    SegmentCue {
+                        timestamp: Some("00:00:01.000".to_string()),
+                        end_timestamp: None,
+                        text: "Let's".to_string(),
+                        new_paragraph: false,
+                    },
+                    SegmentCue {
+                        timestamp: Some("00:00:01.500".to_string()),
+                        end_timestamp: Some("00:00:02.000".to_string()),
+                        text: "begin.".to_string(),
+                        new_paragraph: false,
+                    },
+                ],
+            },
+            SpeakerSegment {
+                speaker: "Bob".to_string(),
+                text: "Sounds <good>.".to_string(),
+                timestamp: Some("00:00:03.000".to_string()),
+                timestamps: vec!["00:00:03.000".to_string()],
+                end_timestamp: Some("00:00:04.000".to_string()),
+                cues: Vec::new(),
+            },
+        ];
+        let metadata = DocumentMetadata {
+            title: Some("Weekly sync".to_string()),
+            ..DocumentMetadata::default()
+        };
+        let transcript = Transcript {
+            segments: &segments,
+            cues: &[],
+            metadata: &metadata,
+        };
+
+        let path = std::env::temp_dir().join(name);
+        fs::write(&path, source).unwrap();
+        let result = render_template(
+            &path,
+            &transcript,
+            TimestampMode::First,
+            &TimestampFormat::default(),
+        );
+        fs::remove_file(&path).ok();
+        result
+    }
+
+    #[test]
+    fn test_render_template() {
+        let output = render(
+            "test_render_template.hbs",
+            "# {{title}}\n\
+             {{#each participants}}{{name}} ({{words}} words)\n{{/each}}\
+             {{#each segments}}{{#if timestamp}}[{{timestamp}}] {{/if}}{{speaker}}: {{text}} ({{start}}-{{end}})\n{{/each}}",
+        )
+        .unwrap();
+
+        // Values are inserted without HTML or Markdown escaping
+        assert_eq!(
+            output,
+            "# Weekly sync\n\
+             John*Doe (2 words)\n\
+             Bob (2 words)\n\
+             [00:00:01.000] John*Doe: Let's begin. (00:00:01.000-00:00:02.000)\n\
+             [00:00:03.000] Bob: Sounds <good>. (00:00:03.000-00:00:04.000)\n"
+        );
+    }
+
+    #[test]
+    fn test_render_template_cues() {
+        let output = render(
+            "test_render_template_cues.hbs",
+            "{{#each segments}}{{#each cues}}[{{start}}] {{text}}\n{{/each}}{{/each}}",
+        )
+        .unwrap();
+
+        assert_eq!(output, "[00:00:01.000] Let's\n[00:00:01.500] begin.\n");
+    }
+
+    #[test]
+    fn test_template_errors_report_line() {
+        // Unclosed block
+        let error = render(
+            "test_template_syntax.hbs",
+            "# {{title}}\n\n{{#each segments}}\n",
+        )
+        .unwrap_err();
+        assert!(matches!(error, VttError::TemplateError { .. }));
+
+        // Unknown field, on line 3
+        let error = render("test_template_field.hbs", "# {{title}}\n\n{{titel}}\n").unwrap_err();
+        match error {
+            VttError::TemplateError { line, .. } => assert_eq!(line, Some(3)),
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+}
@@ -0,0 +1,595 @@
+//! WebVTT timestamp parsing and formatting.
+//!
+//! Cue timestamps are carried through the pipeline as `HH:MM:SS.mmm` strings. This
+//! module converts them to and from milliseconds so that offsets and durations can
+//! be computed without losing the original string representation.
+//!
+//! [`TimestampFormat`] decides how formatters display a timestamp: as parsed by
+//! default, or with a chosen precision, shifted by an offset, as wall-clock times from
+//! a start time, or relative to the previous speaker turn.
+
+use crate::cli::{TimestampMode, TimestampPrecision};
+use crate::consolidator::SpeakerSegment;
+
+/// Parse a WebVTT timestamp (`HH:MM:SS.mmm` or `MM:SS.mmm`) into milliseconds.
+///
+/// Returns `None` if the string is not a valid timestamp.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert_eq!(parse_timestamp("00:01:02.500"), Some(62_500));
+/// assert_eq!(parse_timestamp("01:02.500"), Some(62_500));
+/// ```
+pub fn parse_timestamp(timestamp: &str) -> Option<u64> {
+    let (clock, millis) = timestamp.trim().split_once('.')?;
+    if millis.len() != 3 || !millis.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let parts: Vec<&str> = clock.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [h, m, s] => (*h, *m, *s),
+        [m, s] => ("0", *m, *s),
+        _ => return None,
+    };
+
+    if minutes.len() != 2 || seconds.len() != 2 {
+        return None;
+    }
+
+    let hours: u64 = hours.parse().ok()?;
+    let minutes: u64 = minutes.parse().ok()?;
+    let seconds: u64 = seconds.parse().ok()?;
+    let millis: u64 = millis.parse().ok()?;
+
+    if minutes > 59 || seconds > 59 {
+        return None;
+    }
+
+    Some(((hours * 60 + minutes) * 60 + seconds) * 1000 + millis)
+}
+
+/// Format milliseconds as a WebVTT timestamp (`HH:MM:SS.mmm`).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert_eq!(format_timestamp(62_500), "00:01:02.500");
+/// ```
+pub fn format_timestamp(millis: u64) -> String {
+    let hours = millis / 3_600_000;
+    let minutes = (millis / 60_000) % 60;
+    let seconds = (millis / 1000) % 60;
+    let millis = millis % 1000;
+    format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, seconds, millis)
+}
+
+/// How formatters display cue timestamps.
+///
+/// Only the displayed text changes; anchors, links and structured output keep the
+/// original timestamps.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimestampFormat {
+    /// How precisely to show times; `None` shows timestamps as parsed, or wall-clock
+    /// times as `HH:MM:SS`
+    pub precision: Option<TimestampPrecision>,
+    /// Milliseconds added to every timestamp (negative to subtract), stopping at zero
+    pub offset: i64,
+    /// Time of day the recording started, in milliseconds since midnight; when set,
+    /// timestamps are shown as wall-clock times
+    pub clock_start: Option<u64>,
+    /// Show each speaker turn after the first as `+Δ` since the previous turn
+    pub relative: bool,
+}
+
+impl TimestampFormat {
+    /// The text to display for a timestamp.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let format = TimestampFormat {
+    ///     clock_start: Some(54_000_000), // 15:00
+    ///     ..TimestampFormat::default()
+    /// };
+    /// assert_eq!(format.display("00:12:34.567"), "15:12:34");
+    /// ```
+    pub fn display(&self, timestamp: &str) -> String {
+        let Some(millis) = parse_timestamp(timestamp) else {
+            return timestamp.to_string();
+        };
+        let millis = millis.saturating_add_signed(self.offset);
+
+        match (self.clock_start, self.precision) {
+            // Wrap past midnight
+            (Some(start), precision) => format_precision(
+                (start + millis) % 86_400_000,
+                precision.unwrap_or(TimestampPrecision::Seconds),
+            ),
+            (None, Some(precision)) => format_precision(millis, precision),
+            (None, None) if self.offset == 0 => timestamp.to_string(),
+            (None, None) => format_timestamp(millis),
+        }
+    }
+
+    /// The timestamp labels of speaker turns, in order.
+    ///
+    /// A turn without a timestamp to show gets `None`. With `relative`, every turn
+    /// after the first timestamped one is labeled with the time since the previous
+    /// turn, as `+MM:SS` unless a precision is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `segments` - The consolidated speaker segments
+    /// * `timestamp_mode` - Which timestamp of each segment to show
+    ///
+    /// # Returns
+    ///
+    /// One label per segment.
+    pub fn turn_labels(
+        &self,
+        segments: &[SpeakerSegment],
+        timestamp_mode: TimestampMode,
+    ) -> Vec<Option<String>> {
+        let mut previous: Option<u64> = None;
+
+        segments
+            .iter()
+            .map(|segment| {
+                let timestamp = segment.display_timestamp(timestamp_mode)?;
+                let millis = parse_timestamp(timestamp);
+                let label = match (self.relative, previous, millis) {
+                    (true, Some(previous), Some(millis)) => format!(
+                        "+{}",
+                        format_precision(
+                            millis.saturating_sub(previous),
+                            self.precision.unwrap_or(TimestampPrecision::Minutes),
+                        )
+                    ),
+                    _ => self.display(timestamp),
+                };
+                previous = millis.or(previous);
+                Some(label)
+            })
+            .collect()
+    }
+
+    /// The segment text with a `[time]` before each cue after the first under
+    /// `TimestampMode::Each`, and before each paragraph after the first under
+    /// `TimestampMode::First` (see [`SpeakerSegment::paragraphs`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `segment` - The speaker segment
+    /// * `timestamp_mode` - How to include timestamps (None, First, or Each)
+    /// * `escape` - Escapes the cue text for the output format
+    /// * `paragraph_break` - Goes between the paragraphs of the turn
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let text = format.inline_text(&segment, TimestampMode::Each, str::to_string, "\n\n");
+    /// // "Hello there. [00:00:02.000] How are you?"
+    /// ```
+    pub fn inline_text<F>(
+        &self,
+        segment: &SpeakerSegment,
+        timestamp_mode: TimestampMode,
+        escape: F,
+        paragraph_break: &str,
+    ) -> String
+    where
+        F: Fn(&str) -> String,
+    {
+        self.paragraph_texts(segment, timestamp_mode, escape)
+            .join(paragraph_break)
+    }
+
+    /// The paragraphs of [`inline_text`](Self::inline_text), for formats that write
+    /// each one as its own element.
+    ///
+    /// # Arguments
+    ///
+    /// * `segment` - The speaker segment
+    /// * `timestamp_mode` - How to include timestamps (None, First, or Each)
+    /// * `escape` - Escapes the cue text for the output format
+    pub fn paragraph_texts<F>(
+        &self,
+        segment: &SpeakerSegment,
+        timestamp_mode: TimestampMode,
+        escape: F,
+    ) -> Vec<String>
+    where
+        F: Fn(&str) -> String,
+    {
+        segment
+            .paragraphs(timestamp_mode)
+            .into_iter()
+            .map(|parts| {
+                parts
+                    .into_iter()
+                    .map(|(timestamp, text)| match timestamp {
+                        Some(timestamp) => {
+                            format!("[{}] {}", self.display(timestamp), escape(text))
+                        }
+                        None => escape(text),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+}
+
+/// Format milliseconds with the given precision.
+fn format_precision(millis: u64, precision: TimestampPrecision) -> String {
+    let seconds = millis / 1000;
+    match precision {
+        TimestampPrecision::Millis => format_timestamp(millis),
+        TimestampPrecision::Seconds => format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            (seconds / 60) % 60,
+            seconds % 60
+        ),
+        // Minutes keep counting past the hour
+        TimestampPrecision::Minutes => format!("{:02}:{:02}", seconds / 60, seconds % 60),
+    }
+}
+
+/// A time of day, optionally with its offset from UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockTime {
+    /// Milliseconds since midnight
+    pub time_of_day: u64,
+    /// Minutes east of UTC, if known
+    pub utc_offset: Option<i32>,
+}
+
+impl ClockTime {
+    /// Parse `HH:MM` or `HH:MM:SS`, optionally followed by `Z` or a `±HH:MM` UTC offset.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let time = ClockTime::parse("14:00Z").unwrap();
+    /// assert_eq!(time.time_of_day, 50_400_000);
+    /// assert_eq!(time.utc_offset, Some(0));
+    /// ```
+    pub fn parse(text: &str) -> Option<ClockTime> {
+        let text = text.trim();
+        let split = text.find(['Z', 'z', '+', '-']).unwrap_or(text.len());
+        let (time, zone) = text.split_at(split);
+        let utc_offset = if zone.is_empty() {
+            None
+        } else {
+            Some(parse_utc_offset(zone)?)
+        };
+
+        let parts: Vec<&str> = time.split(':').collect();
+        let (hours, minutes, seconds) = match parts.as_slice() {
+            [h, m] => (*h, *m, "00"),
+            [h, m, s] => (*h, *m, *s),
+            _ => return None,
+        };
+        if [hours, minutes, seconds]
+            .iter()
+            .any(|part| part.len() != 2 || !part.chars().all(|c| c.is_ascii_digit()))
+        {
+            return None;
+        }
+        let (hours, minutes, seconds): (u64, u64, u64) = (
+            hours.parse().ok()?,
+            minutes.parse().ok()?,
+            seconds.parse().ok()?,
+        );
+        if hours > 23 || minutes > 59 || seconds > 59 {
+            return None;
+        }
+
+        Some(ClockTime {
+            time_of_day: ((hours * 60 + minutes) * 60 + seconds) * 1000,
+            utc_offset,
+        })
+    }
+
+    /// The time of day in another time zone, given as minutes east of UTC.
+    ///
+    /// Returns `None` if this time's own UTC offset is unknown.
+    pub fn in_zone(&self, utc_offset: i32) -> Option<u64> {
+        let shift = i64::from(utc_offset - self.utc_offset?) * 60_000;
+        Some((self.time_of_day as i64 + shift).rem_euclid(86_400_000) as u64)
+    }
+}
+
+/// Parse a UTC offset: `Z`, `UTC`, `±HH:MM`, `±HHMM` or `±HH`, as minutes east of UTC.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert_eq!(parse_utc_offset("+05:30"), Some(330));
+/// assert_eq!(parse_utc_offset("Z"), Some(0));
+/// ```
+pub fn parse_utc_offset(text: &str) -> Option<i32> {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("Z") || text.eq_ignore_ascii_case("UTC") {
+        return Some(0);
+    }
+
+    let (sign, rest) = match text.split_at_checked(1)? {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let digits = rest.replace(':', "");
+    if !matches!(digits.len(), 2 | 4) || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits
+        .get(2..)
+        .filter(|m| !m.is_empty())
+        .map_or(Ok(0), str::parse)
+        .ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+
+    Some(sign * (hours * 60 + minutes))
+}
+
+/// Parse a signed duration, `[+-]HH:MM:SS[.mmm]` or `[+-]MM:SS[.mmm]`, into milliseconds.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert_eq!(parse_offset("-00:01:30"), Some(-90_000));
+/// ```
+pub fn parse_offset(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (sign, duration) = match text.split_at_checked(1)? {
+        ("-", rest) => (-1, rest),
+        ("+", rest) => (1, rest),
+        _ => (1, text),
+    };
+    let millis = if duration.contains('.') {
+        parse_timestamp(duration)?
+    } else {
+        parse_timestamp(&format!("{}.000", duration))?
+    };
+
+    Some(sign * i64::try_from(millis).ok()?)
+}
+
+/// Parse a duration into milliseconds: seconds (`90`), units (`90s`, `3m`, `1m30s`,
+/// `1h`), or `HH:MM:SS[.mmm]` / `MM:SS[.mmm]`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert_eq!(parse_duration("1m30s"), Some(90_000));
+/// ```
+pub fn parse_duration(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if text.contains(':') {
+        if text.starts_with(['+', '-']) {
+            return None;
+        }
+        return u64::try_from(parse_offset(text)?).ok();
+    }
+    if let Ok(seconds) = text.parse::<u64>() {
+        return seconds.checked_mul(1000);
+    }
+
+    let mut millis: u64 = 0;
+    let mut number = String::new();
+    let mut last_unit = None;
+    for ch in text.chars() {
+        if ch.is_ascii_digit() {
+            number.push(ch);
+            continue;
+        }
+        let unit = match ch {
+            'h' => 3_600_000,
+            'm' => 60_000,
+            's' => 1000,
+            _ => return None,
+        };
+        // Units must be given largest first, each at most once
+        if number.is_empty() || last_unit.is_some_and(|last| last <= unit) {
+            return None;
+        }
+        millis = millis.checked_add(number.parse::<u64>().ok()?.checked_mul(unit)?)?;
+        number.clear();
+        last_unit = Some(unit);
+    }
+
+    if number.is_empty() && last_unit.is_some() {
+        Some(millis)
+    } else {
+        None
+    }
+}
+
+/// Format a duration compactly, e.g. `45s`, `3m12s` or `1h5m`, leaving out zero parts
+/// and rounding down to the second.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert_eq!(format_duration(192_400), "3m12s");
+/// ```
+pub fn format_duration(millis: u64) -> String {
+    let seconds = millis / 1000;
+    let parts = [
+        (seconds / 3600, "h"),
+        ((seconds / 60) % 60, "m"),
+        (seconds % 60, "s"),
+    ];
+
+    let result: String = parts
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect();
+    if result.is_empty() {
+        "0s".to_string()
+    } else {
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("00:00:00.000"), Some(0));
+        assert_eq!(parse_timestamp("00:00:01.250"), Some(1_250));
+        assert_eq!(parse_timestamp("01:02:03.004"), Some(3_723_004));
+        assert_eq!(parse_timestamp("02:03.004"), Some(123_004));
+
+        // Invalid timestamps
+        assert_eq!(parse_timestamp(""), None);
+        assert_eq!(parse_timestamp("00:00:01"), None);
+        assert_eq!(parse_timestamp("00:60:00.000"), None);
+        assert_eq!(parse_timestamp("00:00:01.5"), None);
+        assert_eq!(parse_timestamp("aa:bb:cc.ddd"), None);
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "00:00:00.000");
+        assert_eq!(format_timestamp(1_250), "00:00:01.250");
+        assert_eq!(format_timestamp(3_723_004), "01:02:03.004");
+    }
+
+    #[test]
+    fn test_timestamp_format_display() {
+        assert_eq!(
+            TimestampFormat::default().display("00:12:34.567"),
+            "00:12:34.567"
+        );
+
+        let clock = TimestampFormat {
+            clock_start: Some(54_000_000),
+            ..TimestampFormat::default()
+        };
+        assert_eq!(clock.display("00:12:34.567"), "15:12:34");
+        assert_eq!(clock.display("09:30:00.000"), "00:30:00");
+        // Text that isn't a timestamp is shown unchanged
+        assert_eq!(clock.display("soon"), "soon");
+
+        let shifted = TimestampFormat {
+            precision: Some(TimestampPrecision::Minutes),
+            offset: -60_000,
+            ..TimestampFormat::default()
+        };
+        assert_eq!(shifted.display("01:02:03.456"), "61:03");
+        // Shifting stops at zero
+        assert_eq!(shifted.display("00:00:30.000"), "00:00");
+
+        let offset_only = TimestampFormat {
+            offset: 1_500,
+            ..TimestampFormat::default()
+        };
+        assert_eq!(offset_only.display("00:00:01.000"), "00:00:02.500");
+    }
+
+    #[test]
+    fn test_turn_labels_relative() {
+        let segment = |start: &str| SpeakerSegment {
+            speaker: "Alice".to_string(),
+            text: "Hi".to_string(),
+            timestamp: Some(start.to_string()),
+            timestamps: vec![start.to_string()],
+            end_timestamp: None,
+            cues: Vec::new(),
+        };
+        let segments = vec![
+            segment("00:00:05.000"),
+            segment("00:00:17.500"),
+            segment("00:02:20.000"),
+        ];
+        let format = TimestampFormat {
+            relative: true,
+            ..TimestampFormat::default()
+        };
+
+        assert_eq!(
+            format.turn_labels(&segments, TimestampMode::First),
+            vec![
+                Some("00:00:05.000".to_string()),
+                Some("+00:12".to_string()),
+                Some("+02:02".to_string()),
+            ]
+        );
+        assert_eq!(
+            format.turn_labels(&segments, TimestampMode::None),
+            vec![None, None, None]
+        );
+    }
+
+    #[test]
+    fn test_clock_time() {
+        let time = ClockTime::parse("09:30").unwrap();
+        assert_eq!(time.time_of_day, 34_200_000);
+        assert_eq!(time.utc_offset, None);
+        assert_eq!(time.in_zone(60), None);
+
+        let time = ClockTime::parse("23:30:00+01:00").unwrap();
+        assert_eq!(time.utc_offset, Some(60));
+        // 22:30 UTC is 04:00 the next day in India
+        assert_eq!(time.in_zone(330), Some(14_400_000));
+        assert_eq!(
+            ClockTime::parse("14:00Z").unwrap().in_zone(-300),
+            Some(32_400_000)
+        );
+
+        assert_eq!(ClockTime::parse("24:00"), None);
+        assert_eq!(ClockTime::parse("9:30"), None);
+        assert_eq!(ClockTime::parse("09:30+1"), None);
+    }
+
+    #[test]
+    fn test_parse_utc_offset_and_offset() {
+        assert_eq!(parse_utc_offset("Z"), Some(0));
+        assert_eq!(parse_utc_offset("+05:30"), Some(330));
+        assert_eq!(parse_utc_offset("-0800"), Some(-480));
+        assert_eq!(parse_utc_offset("+02"), Some(120));
+        assert_eq!(parse_utc_offset("05:30"), None);
+
+        assert_eq!(parse_offset("00:01:30"), Some(90_000));
+        assert_eq!(parse_offset("-01:30.500"), Some(-90_500));
+        assert_eq!(parse_offset("+00:00:02"), Some(2_000));
+        assert_eq!(parse_offset("90"), None);
+    }
+
+    #[test]
+    fn test_parse_and_format_duration() {
+        assert_eq!(parse_duration("90"), Some(90_000));
+        assert_eq!(parse_duration("90s"), Some(90_000));
+        assert_eq!(parse_duration("1m30s"), Some(90_000));
+        assert_eq!(parse_duration("2h"), Some(7_200_000));
+        assert_eq!(parse_duration("00:01:30"), Some(90_000));
+        assert_eq!(parse_duration("01:30.500"), Some(90_500));
+        assert_eq!(parse_duration("30s1m"), None);
+        assert_eq!(parse_duration("1m30"), None);
+        assert_eq!(parse_duration("-00:01:30"), None);
+        assert_eq!(parse_duration("soon"), None);
+
+        assert_eq!(format_duration(192_400), "3m12s");
+        assert_eq!(format_duration(45_000), "45s");
+        assert_eq!(format_duration(3_900_000), "1h5m");
+        assert_eq!(format_duration(500), "0s");
+    }
+
+    #[test]
+    fn test_timestamp_round_trip() {
+        for ts in ["00:00:00.000", "00:12:34.567", "10:59:59.999"] {
+            assert_eq!(format_timestamp(parse_timestamp(ts).unwrap()), ts);
+        }
+    }
+}
@@ -0,0 +1,441 @@
+//! Automatic topic segmentation for transcripts without a chapters track.
+//!
+//! `--topics` groups the speaker turns into sections, breaking between turns at long
+//! silences and where the vocabulary shifts. Topic shifts are found TextTiling-style:
+//! at every turn boundary, the content words just before it are compared with those
+//! just after it, and boundaries in the deepest valleys of that cohesion score become
+//! section breaks. Each section is headed by its top keywords.
+//!
+//! Sections are returned as [`Chapter`]s, so they get the same headings, anchors and
+//! table of contents as a chapters track. They always break between turns, never
+//! inside one.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let sections = segment_topics(&segments);
+//! // sections[0].title == "Budget, hiring, quarter"
+//! ```
+
+use crate::chapters::{Chapter, unique_id};
+use crate::consolidator::{SpeakerSegment, pause_before, slug};
+use std::collections::HashMap;
+
+/// A pause between turns at least this long always ends a section.
+const SILENCE_GAP_MS: u64 = 30_000;
+
+/// Number of content words compared on each side of a turn boundary.
+const BLOCK_WORDS: usize = 80;
+
+/// Minimum number of content words in a section.
+const MIN_SECTION_WORDS: usize = 150;
+
+/// Number of keywords in a section heading.
+const HEADING_KEYWORDS: usize = 3;
+
+/// Words too common in conversation to say anything about the topic.
+#[rustfmt::skip]
+const STOP_WORDS: &[&str] = &[
+    "about", "actually", "after", "again", "all", "also", "and", "any", "are", "around",
+    "back", "because", "been", "before", "being", "but", "can", "could", "did", "does",
+    "doing", "don't", "down", "each", "even", "for", "from", "get", "getting", "going",
+    "gonna", "good", "got", "had", "has", "have", "having", "her", "here", "him", "his",
+    "how", "i'd", "i'll", "i'm", "i've", "into", "it's", "its", "just", "kind", "know",
+    "let", "let's", "like", "look", "lot", "make", "maybe", "mean", "more", "much", "need",
+    "not", "now", "off", "okay", "one", "only", "other", "our", "out", "over", "pretty",
+    "put", "really", "right", "said", "say", "see", "she", "should", "some", "something",
+    "sort", "still", "sure", "take", "talk", "than", "thank", "thanks", "that", "that's",
+    "the", "their", "them", "then", "there", "there's", "these", "they", "they're", "thing",
+    "things", "think", "this", "those", "through", "too", "two", "um", "uh", "very", "want",
+    "was", "way", "we'll", "we're", "we've", "well", "were", "what", "what's", "when",
+    "where", "which", "while", "who", "why", "will", "with", "would", "yeah", "yes", "you",
+    "you're", "your",
+];
+
+/// Split the transcript into sections at long silences and topic shifts.
+///
+/// Short transcripts become a single section. Turns without a start time never begin
+/// a section.
+///
+/// # Arguments
+///
+/// * `segments` - The consolidated speaker segments
+///
+/// # Returns
+///
+/// One chapter per section, in order, titled with the section's top keywords (or
+/// "Section N" when it has none) and with a `section-...` anchor id.
+pub fn segment_topics(segments: &[SpeakerSegment]) -> Vec<Chapter> {
+    let words: Vec<Vec<String>> = segments
+        .iter()
+        .map(|segment| content_words(&segment.text))
+        .collect();
+
+    let Some(first) = segments
+        .iter()
+        .position(|segment| !segment.timestamps.is_empty())
+    else {
+        return Vec::new();
+    };
+    let mut starts = vec![first];
+    starts.extend(
+        find_boundaries(segments, &words)
+            .into_iter()
+            .filter(|&index| index > first && !segments[index].timestamps.is_empty()),
+    );
+
+    let mut ends: Vec<usize> = starts[1..].to_vec();
+    ends.push(segments.len());
+    // The first section also takes any untimed turns before it
+    let ranges: Vec<(usize, usize)> = starts
+        .iter()
+        .zip(&ends)
+        .enumerate()
+        .map(|(index, (&start, &end))| (if index == 0 { 0 } else { start }, end))
+        .collect();
+    let titles = headings(&words, &ranges);
+
+    let mut sections: Vec<Chapter> = Vec::with_capacity(ranges.len());
+    for ((start, end), title) in starts.iter().zip(&ends).zip(titles) {
+        let title = title.unwrap_or_else(|| format!("Section {}", sections.len() + 1));
+        let id = unique_id(&format!("section-{}", slug(&title)), &sections);
+        sections.push(Chapter {
+            title,
+            start: segments[*start].timestamps[0].clone(),
+            end: segments[*end - 1].end_timestamp.clone(),
+            id,
+        });
+    }
+
+    sections
+}
+
+/// The lowercase content words of a text, without stop words, numbers and words
+/// shorter than three letters.
+fn content_words(text: &str) -> Vec<String> {
+    text.split(|ch: char| !ch.is_alphanumeric() && ch != '\'' && ch != '’')
+        .map(|word| word.replace('’', "'").trim_matches('\'').to_lowercase())
+        .map(|word| match word.strip_suffix("'s") {
+            Some(stem) if !STOP_WORDS.contains(&word.as_str()) => stem.to_string(),
+            _ => word,
+        })
+        .filter(|word| word.chars().count() >= 3)
+        .filter(|word| word.chars().any(char::is_alphabetic))
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// The indices of the turns that start a new section (never the first turn).
+///
+/// Every long silence is a break, however short the sections around it. Topic shifts
+/// are then taken from the deepest valley down, skipping any that would leave a
+/// section under [`MIN_SECTION_WORDS`].
+fn find_boundaries(segments: &[SpeakerSegment], words: &[Vec<String>]) -> Vec<usize> {
+    if segments.len() < 2 {
+        return Vec::new();
+    }
+
+    // Content word offset at which each turn starts
+    let mut offsets = Vec::with_capacity(segments.len() + 1);
+    let mut total = 0;
+    for turn in words {
+        offsets.push(total);
+        total += turn.len();
+    }
+    offsets.push(total);
+    let flat: Vec<&str> = words.iter().flatten().map(String::as_str).collect();
+
+    // Cohesion across each turn boundary, then how deep a valley it sits in
+    let scores: Vec<f64> = (1..segments.len())
+        .map(|index| {
+            let offset = offsets[index];
+            cosine(
+                &flat[offset.saturating_sub(BLOCK_WORDS)..offset],
+                &flat[offset..(offset + BLOCK_WORDS).min(total)],
+            )
+        })
+        .collect();
+    let depths = depth_scores(&scores);
+    let mean = depths.iter().sum::<f64>() / depths.len() as f64;
+    let variance = depths
+        .iter()
+        .map(|depth| (depth - mean).powi(2))
+        .sum::<f64>()
+        / depths.len() as f64;
+    let cutoff = mean - variance.sqrt() / 2.0;
+
+    let silent =
+        |index: usize| pause_before(segments, index).is_some_and(|pause| pause >= SILENCE_GAP_MS);
+
+    let mut candidates: Vec<(bool, f64, usize)> = (1..segments.len())
+        .map(|index| (silent(index), depths[index - 1], index))
+        .filter(|&(silent, depth, _)| silent || (depth > 0.0 && depth > cutoff))
+        .collect();
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.total_cmp(&a.1)));
+
+    let mut boundaries: Vec<usize> = Vec::new();
+    for (silent, _, index) in candidates {
+        if silent {
+            boundaries.push(index);
+            continue;
+        }
+        let before = boundaries
+            .iter()
+            .filter(|&&boundary| boundary < index)
+            .max()
+            .map_or(0, |&boundary| offsets[boundary]);
+        let after = boundaries
+            .iter()
+            .filter(|&&boundary| boundary > index)
+            .min()
+            .map_or(total, |&boundary| offsets[boundary]);
+        if offsets[index] - before >= MIN_SECTION_WORDS
+            && after - offsets[index] >= MIN_SECTION_WORDS
+        {
+            boundaries.push(index);
+        }
+    }
+
+    boundaries.sort_unstable();
+    boundaries
+}
+
+/// How far each score sits below the peaks on either side of it.
+fn depth_scores(scores: &[f64]) -> Vec<f64> {
+    scores
+        .iter()
+        .enumerate()
+        .map(|(index, &score)| {
+            let mut left = score;
+            for &previous in scores[..index].iter().rev() {
+                if previous < left {
+                    break;
+                }
+                left = previous;
+            }
+            let mut right = score;
+            for &next in &scores[index + 1..] {
+                if next < right {
+                    break;
+                }
+                right = next;
+            }
+            (left - score) + (right - score)
+        })
+        .collect()
+}
+
+/// Cosine similarity of the word counts of two blocks of words.
+fn cosine(left: &[&str], right: &[&str]) -> f64 {
+    fn counts<'a>(block: &[&'a str]) -> HashMap<&'a str, f64> {
+        let mut counts = HashMap::new();
+        for &word in block {
+            *counts.entry(word).or_default() += 1.0;
+        }
+        counts
+    }
+    let (left, right) = (counts(left), counts(right));
+
+    let dot: f64 = left
+        .iter()
+        .filter_map(|(word, count)| right.get(word).map(|other| count * other))
+        .sum();
+    let norm = |counts: &HashMap<&str, f64>| counts.values().map(|c| c * c).sum::<f64>().sqrt();
+    let norms = norm(&left) * norm(&right);
+
+    if norms == 0.0 { 0.0 } else { dot / norms }
+}
+
+/// A heading for each section from the words most frequent in it and least frequent in
+/// the other sections, or `None` for a section without content words.
+fn headings(words: &[Vec<String>], ranges: &[(usize, usize)]) -> Vec<Option<String>> {
+    let sections: Vec<Vec<&str>> = ranges
+        .iter()
+        .map(|&(start, end)| {
+            words[start..end]
+                .iter()
+                .flatten()
+                .map(String::as_str)
+                .collect()
+        })
+        .collect();
+
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for section in &sections {
+        let mut seen: Vec<&str> = section.clone();
+        seen.sort_unstable();
+        seen.dedup();
+        for word in seen {
+            *document_frequency.entry(word).or_default() += 1;
+        }
+    }
+
+    sections
+        .iter()
+        .map(|section| {
+            // Count words in order of first use, so ties go to the earlier word
+            let mut counts: Vec<(&str, usize)> = Vec::new();
+            for &word in section {
+                match counts.iter_mut().find(|(seen, _)| *seen == word) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((word, 1)),
+                }
+            }
+
+            let weight = |&(word, count): &(&str, usize)| {
+                let idf = (sections.len() as f64 / document_frequency[word] as f64).ln() + 1.0;
+                count as f64 * idf
+            };
+            let mut ranked: Vec<(f64, &str)> = counts
+                .iter()
+                .map(|entry| (weight(entry), entry.0))
+                .collect();
+            ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+            let keywords: Vec<&str> = ranked
+                .iter()
+                .take(HEADING_KEYWORDS)
+                .map(|&(_, word)| word)
+                .collect();
+            let heading = keywords.join(", ");
+            let mut chars = heading.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(speaker: &str, start: &str, end: &str, text: &str) -> SpeakerSegment {
+        SpeakerSegment {
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+            timestamp: None,
+            timestamps: vec![start.to_string()],
+            end_timestamp: Some(end.to_string()),
+            cues: Vec::new(),
+        }
+    }
+
+    fn repeat(text: &str, times: usize) -> String {
+        vec![text; times].join(" ")
+    }
+
+    #[test]
+    fn test_content_words() {
+        assert_eq!(
+            content_words("Yeah, so the budget's 2025 forecast — it’s ready, OK?"),
+            vec!["budget", "forecast", "ready"]
+        );
+    }
+
+    #[test]
+    fn test_short_transcript_is_one_section() {
+        let segments = vec![
+            turn(
+                "Alice",
+                "00:00:01.000",
+                "00:00:04.000",
+                "The budget forecast.",
+            ),
+            turn("Bob", "00:00:05.000", "00:00:08.000", "Hiring plans."),
+        ];
+        let sections = segment_topics(&segments);
+
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].title, "Budget, forecast, hiring");
+        assert_eq!(sections[0].id, "section-budget-forecast-hiring");
+        assert_eq!(sections[0].start, "00:00:01.000");
+        assert_eq!(sections[0].end.as_deref(), Some("00:00:08.000"));
+    }
+
+    #[test]
+    fn test_topic_shift_starts_a_section() {
+        let budget = repeat("budget forecast spending quarter revenue", 8);
+        let hiring = repeat("hiring candidates interviews engineers offers", 8);
+        let segments = vec![
+            turn("Alice", "00:00:00.000", "00:00:20.000", &budget),
+            turn("Bob", "00:00:21.000", "00:00:40.000", &budget),
+            turn("Alice", "00:00:41.000", "00:01:00.000", &budget),
+            turn("Bob", "00:01:01.000", "00:01:20.000", &budget),
+            turn("Alice", "00:01:21.000", "00:01:40.000", &hiring),
+            turn("Bob", "00:01:41.000", "00:02:00.000", &hiring),
+            turn("Alice", "00:02:01.000", "00:02:20.000", &hiring),
+            turn("Bob", "00:02:21.000", "00:02:40.000", &hiring),
+        ];
+        let sections = segment_topics(&segments);
+
+        let starts: Vec<&str> = sections
+            .iter()
+            .map(|section| section.start.as_str())
+            .collect();
+        assert_eq!(starts, vec!["00:00:00.000", "00:01:21.000"]);
+        assert_eq!(sections[0].title, "Budget, forecast, spending");
+        assert_eq!(sections[1].title, "Hiring, candidates, interviews");
+    }
+
+    #[test]
+    fn test_silence_starts_a_section() {
+        let text = repeat("roadmap milestones launch", 30);
+        let segments = vec![
+            turn("Alice", "00:00:00.000", "00:01:00.000", &text),
+            turn("Alice", "00:01:01.000", "00:02:00.000", &text),
+            turn("Bob", "00:03:00.000", "00:04:00.000", &text),
+            turn("Bob", "00:04:01.000", "00:05:00.000", &text),
+        ];
+        let sections = segment_topics(&segments);
+
+        let starts: Vec<&str> = sections
+            .iter()
+            .map(|section| section.start.as_str())
+            .collect();
+        assert_eq!(starts, vec!["00:00:00.000", "00:03:00.000"]);
+        // Sections with the same keywords still get distinct anchors
+        assert_eq!(sections[0].id, "section-roadmap-milestones-launch");
+        assert_eq!(sections[1].id, "section-roadmap-milestones-launch-2");
+    }
+
+    #[test]
+    fn test_silence_ends_a_short_section() {
+        let segments = vec![
+            turn(
+                "Alice",
+                "00:00:00.000",
+                "00:00:05.000",
+                "Budget forecast first.",
+            ),
+            turn(
+                "Bob",
+                "00:00:06.000",
+                "00:00:10.000",
+                "Agreed on the budget.",
+            ),
+            turn(
+                "Alice",
+                "00:02:00.000",
+                "00:02:05.000",
+                "Hiring plans next.",
+            ),
+        ];
+        let sections = segment_topics(&segments);
+
+        let starts: Vec<&str> = sections
+            .iter()
+            .map(|section| section.start.as_str())
+            .collect();
+        assert_eq!(starts, vec!["00:00:00.000", "00:02:00.000"]);
+        assert_eq!(sections[1].title, "Hiring, plans, next");
+    }
+
+    #[test]
+    fn test_depth_scores() {
+        let depths = depth_scores(&[0.8, 0.2, 0.6, 0.5, 0.9]);
+        assert!((depths[1] - 1.0).abs() < 1e-9);
+        assert!((depths[3] - 0.5).abs() < 1e-9);
+        assert_eq!(depths[0], 0.0);
+    }
+}
@@ -218,6 +218,8 @@ fn test_include_timestamps_each() {
     let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(stdout.contains("[00:00:00.000]"));
     assert!(stdout.contains("**Alice:**"));
+    // The second cue of the turn gets its own inline timestamp
+    assert!(stdout.contains("[00:00:00.000] **Alice:** Hello [00:00:02.000] World"));
 }
 
 #[test]
@@ -605,3 +607,1100 @@ fn test_explicit_output_skips_auto_increment() {
     
     assert!(!output.status.success(), "Should fail when explicit output exists");
 }
+
+#[test]
+fn test_hls_playlist_input() {
+    let temp_dir = TempDir::new().unwrap();
+    let playlist = create_test_vtt(
+        &temp_dir,
+        "captions.m3u8",
+        "#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXTINF:10.0,\nseg0.vtt\n#EXTINF:10.0,\nseg1.vtt\n#EXT-X-ENDLIST\n",
+    );
+    create_test_vtt(
+        &temp_dir,
+        "seg0.vtt",
+        "WEBVTT\nX-TIMESTAMP-MAP=MPEGTS:900000,LOCAL:00:00:00.000\n\n00:00:01.000 --> 00:00:03.000\n<v Alice>Welcome everyone.</v>\n\n00:00:09.000 --> 00:00:11.000\n<v Alice>Let's begin.</v>\n",
+    );
+    create_test_vtt(
+        &temp_dir,
+        "seg1.vtt",
+        "WEBVTT\nX-TIMESTAMP-MAP=MPEGTS:900000,LOCAL:00:00:00.000\n\n00:00:09.000 --> 00:00:11.000\n<v Alice>Let's begin.</v>\n\n00:00:12.000 --> 00:00:14.000\n<v Bob>Thanks Alice.</v>\n",
+    );
+    let output_path = temp_dir.path().join("captions.md");
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&playlist)
+        .arg("--include-timestamps")
+        .arg("first")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+
+    assert!(
+        output.status.success(),
+        "Command failed for HLS playlist: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let markdown = fs::read_to_string(&output_path).expect("Failed to read output file");
+    assert_eq!(
+        markdown,
+        "[00:00:01.000] **Alice:** Welcome everyone. Let's begin.\n\n[00:00:12.000] **Bob:** Thanks Alice.\n\n",
+        "Boundary cue repeated in both segments should appear once"
+    );
+}
+
+/// Encode a Matroska element with an 8-byte size field.
+fn mkv_element(id: &[u8], body: &[u8]) -> Vec<u8> {
+    let mut data = id.to_vec();
+    data.push(0x01);
+    data.extend_from_slice(&(body.len() as u64).to_be_bytes()[1..]);
+    data.extend_from_slice(body);
+    data
+}
+
+/// Build a Matroska file with one S_TEXT/WEBVTT track per (language, cue) pair.
+fn build_test_mkv(tracks: &[(&str, &str)]) -> Vec<u8> {
+    let mut entries = Vec::new();
+    let mut blocks = Vec::new();
+    for (index, (language, payload)) in tracks.iter().enumerate() {
+        let number = index as u8 + 1;
+        entries.extend(mkv_element(
+            &[0xAE],
+            &[
+                mkv_element(&[0xD7], &[number]),
+                mkv_element(&[0x86], b"S_TEXT/WEBVTT"),
+                mkv_element(&[0x22, 0xB5, 0x9C], language.as_bytes()),
+            ]
+            .concat(),
+        ));
+        let mut block = vec![0x80 | number, 0, 0, 0];
+        block.extend_from_slice(payload.as_bytes());
+        blocks.extend(mkv_element(
+            &[0xA0],
+            &[mkv_element(&[0xA1], &block), mkv_element(&[0x9B], &[0x07, 0xD0])].concat(),
+        ));
+    }
+
+    let cluster = mkv_element(
+        &[0x1F, 0x43, 0xB6, 0x75],
+        &[mkv_element(&[0xE7], &[0]), blocks].concat(),
+    );
+    let segment = mkv_element(
+        &[0x18, 0x53, 0x80, 0x67],
+        &[mkv_element(&[0x16, 0x54, 0xAE, 0x6B], &entries), cluster].concat(),
+    );
+    [mkv_element(&[0x1A, 0x45, 0xDF, 0xA3], &[]), segment].concat()
+}
+
+#[test]
+fn test_matroska_track_selection() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = temp_dir.path().join("recording.mkv");
+    fs::write(
+        &input_path,
+        build_test_mkv(&[
+            ("eng", "<v Alice>Good morning."),
+            ("ger", "<v Alice>Guten Morgen."),
+        ]),
+    )
+    .expect("Failed to write test MKV file");
+
+    // Default: first subtitle track
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Command failed for MKV input: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "**Alice:** Good morning.\n\n"
+    );
+
+    // Select by language
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--track")
+        .arg("ger")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(output.status.success(), "Command failed with --track ger");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "**Alice:** Guten Morgen.\n\n"
+    );
+
+    // Unknown track is a usage error
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--track")
+        .arg("fre")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(64), "Should exit with EX_USAGE");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("1: #1 (eng)"), "Should list available tracks: {}", stderr);
+}
+
+#[test]
+fn test_input_format_detection_and_override() {
+    let temp_dir = TempDir::new().unwrap();
+    // WebVTT content with a non-VTT extension is detected from its contents
+    let input_path = create_test_vtt(&temp_dir, "captions.txt", SIMPLE_VTT);
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Detection failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("**Alice:** Hello world"));
+
+    // Forcing a different format is honored
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--from")
+        .arg("hls")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(65), "Should fail to parse as a playlist");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("#EXTM3U"));
+
+    // Unknown format names are rejected by argument parsing
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--from")
+        .arg("docx")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(!output.status.success(), "Unknown --from value should be rejected");
+}
+
+#[test]
+fn test_html_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "meeting.vtt",
+        "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\n<v Alice>Hello & welcome</v>\n\n00:00:03.000 --> 00:00:04.000\n<v Bob>Thanks</v>\n",
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("html")
+        .arg("--include-timestamps")
+        .arg("first")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "HTML conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // The derived output path uses the .html extension
+    let html_path = temp_dir.path().join("meeting.html");
+    let html = fs::read_to_string(&html_path).expect("HTML output should exist");
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>meeting</title>"));
+    assert!(html.contains("<p>Hello &amp; welcome</p>"));
+    assert!(html.contains("href=\"#t-00-00-03-000\""));
+    assert!(html.contains("id=\"search\""));
+}
+
+#[test]
+fn test_html_media_player() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "meeting.vtt",
+        "WEBVTT\n\n00:00:01.000 --> 00:00:02.500\n<v Alice>Hello</v>\n\n00:00:03.000 --> 00:00:04.000\n<v Bob>Hi</v>\n",
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("html")
+        .arg("--media")
+        .arg("meeting.mp3")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "HTML player conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let html = String::from_utf8_lossy(&output.stdout);
+    assert!(html.contains("<audio id=\"player\" controls preload=\"metadata\" src=\"meeting.mp3\">"));
+    assert!(html.contains("data-start=\"1.000\" data-end=\"2.500\""));
+    assert!(html.contains("data-start=\"3.000\" data-end=\"4.000\""));
+
+    // --media only applies to HTML output
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--media")
+        .arg("meeting.mp3")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(64), "--media without --to html is a usage error");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--media requires --to html"));
+}
+
+#[test]
+fn test_json_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "meeting.vtt",
+        "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\n<v Alice>Hello</v>\n\n00:00:02.000 --> 00:00:03.000\n<v Alice>again</v>\n\n00:00:03.000 --> 00:00:04.000\n<v Bob>Hi</v>\n",
+    );
+
+    // JSON segments, written to the derived .json path
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("json")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "JSON conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json = fs::read_to_string(temp_dir.path().join("meeting.json"))
+        .expect("JSON output should exist");
+    assert!(json.contains("\"schema_version\": 1"));
+    assert!(json.contains("\"records\": \"segments\""));
+    assert!(json.contains("\"text\": \"Hello again\""));
+
+    // JSON Lines cues
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("jsonl")
+        .arg("--records")
+        .arg("cues")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 4, "Metadata line plus one line per cue");
+    assert!(lines[0].starts_with("{\"type\":\"metadata\",\"schema_version\":1,\"records\":\"cues\""));
+    assert!(lines[3].contains("\"speaker\":\"Bob\""));
+
+    // --records only applies to JSON output
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--records")
+        .arg("cues")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn test_caption_output_formats() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "captions.vtt",
+        "WEBVTT\n\ncue-1\n00:00:01.000 --> 00:00:02.000 align:start\n<v Alice>Hello <b>there</b></v>\n\n00:00:03.000 --> 00:00:04.000\n<v Bob>Hi</v>\n",
+    );
+
+    // WebVTT output goes to a new file rather than overwriting the input
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("vtt")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "WebVTT conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let vtt = fs::read_to_string(temp_dir.path().join("captions (1).vtt"))
+        .expect("WebVTT output should exist");
+    assert_eq!(
+        vtt,
+        "WEBVTT\n\ncue-1\n00:00:01.000 --> 00:00:02.000 align:start\n<v Alice>Hello there</v>\n\n00:00:03.000 --> 00:00:04.000\n<v Bob>Hi</v>\n"
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("srt")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "1\n00:00:01,000 --> 00:00:02,000\nAlice: Hello there\n\n2\n00:00:03,000 --> 00:00:04,000\nBob: Hi\n"
+    );
+}
+
+#[test]
+fn test_docx_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(&temp_dir, "meeting.vtt", SIMPLE_VTT);
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("docx")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "DOCX conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // A .docx file is a zip package
+    let bytes = fs::read(temp_dir.path().join("meeting.docx")).expect("DOCX output should exist");
+    assert!(bytes.starts_with(b"PK\x03\x04"));
+}
+
+#[test]
+fn test_markup_output_formats() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "meeting.vtt",
+        "WEBVTT - Weekly sync\n\n00:00:01.000 --> 00:00:02.000\n<v Alice>Hello world</v>\n",
+    );
+
+    let cases = [
+        ("org", "meeting.org", "#+TITLE: Weekly sync\n\n[00:00:01.000] *Alice:* Hello world\n\n"),
+        ("asciidoc", "meeting.adoc", "= Weekly sync\n\n[00:00:01.000] *Alice:* Hello world\n\n"),
+        (
+            "rst",
+            "meeting.rst",
+            "Weekly sync\n===========\n\n[00:00:01.000] **Alice:** Hello world\n\n",
+        ),
+    ];
+
+    for (format, filename, expected) in cases {
+        let output = Command::new(get_vtt_to_md_path())
+            .arg(&input_path)
+            .arg("--to")
+            .arg(format)
+            .arg("--include-timestamps")
+            .arg("first")
+            .output()
+            .expect("Failed to execute vtt-to-md");
+        assert!(
+            output.status.success(),
+            "{} conversion failed: {:?}",
+            format,
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let content = fs::read_to_string(temp_dir.path().join(filename))
+            .unwrap_or_else(|_| panic!("{} output should exist", filename));
+        assert_eq!(content, expected, "Unexpected {} output", format);
+    }
+}
+
+#[test]
+fn test_interview_and_screenplay_layouts() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "interview.vtt",
+        "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\n<v Host>How did you start?</v>\n\n00:00:03.000 --> 00:00:04.000\n<v Guest>By accident.</v>\n",
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("qa")
+        .arg("--interviewer")
+        .arg("host")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Q&A conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "**Q:** How did you start?\n\n**A:** By accident.\n\n"
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("fountain")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Title: interview\n\nHOST\nHow did you start?\n\nGUEST\nBy accident.\n\n"
+    );
+
+    // --interviewer only applies to the Q&A layout
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--interviewer")
+        .arg("Host")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn test_pandoc_json_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(&temp_dir, "pandoc.vtt", SIMPLE_VTT);
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("pandoc-json")
+        .arg("--include-timestamps")
+        .arg("first")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Pandoc JSON conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Pandoc JSON shares the .json extension
+    let output_path = temp_dir.path().join("pandoc.json");
+    let content = fs::read_to_string(&output_path).unwrap();
+    assert!(content.contains("\"pandoc-api-version\":[1,23,1]"));
+    assert!(content.contains("\"title\":{\"c\":[{\"c\":\"pandoc\",\"t\":\"Str\"}],\"t\":\"MetaInlines\"}"));
+    assert!(content.contains("[\"\",[\"timestamp\"],[[\"start\",\"00:00:00.000\"],[\"end\",\"00:00:02.000\"]]]"));
+    assert!(content.contains("{\"c\":[{\"c\":\"Alice:\",\"t\":\"Str\"}],\"t\":\"Strong\"}"));
+}
+
+#[test]
+fn test_csv_and_tsv_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "coding.vtt",
+        "WEBVTT\n\n00:00:01.000 --> 00:00:02.500\n<v Alice>Yes, I think so.</v>\n\n00:00:03.000 --> 00:00:04.000\n<v Bob>Agreed</v>\n",
+    );
+
+    // CSV segments with all columns, written to the derived .csv path
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("csv")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "CSV conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let csv = fs::read_to_string(temp_dir.path().join("coding.csv")).unwrap();
+    assert_eq!(
+        csv,
+        "start,end,duration,speaker,words,text\r\n\
+         00:00:01.000,00:00:02.500,1.500,Alice,4,\"Yes, I think so.\"\r\n\
+         00:00:03.000,00:00:04.000,1.000,Bob,1,Agreed\r\n"
+    );
+
+    // TSV cues with selected columns
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("tsv")
+        .arg("--records")
+        .arg("cues")
+        .arg("--columns")
+        .arg("speaker,text")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "speaker\ttext\nAlice\tYes, I think so.\nBob\tAgreed\n"
+    );
+
+    // --columns only applies to tabular output
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--columns")
+        .arg("text")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn test_markdown_layout_styles() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "styles.vtt",
+        "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\n<v Alice>Hello.</v>\n\n00:00:03.000 --> 00:00:04.000\n<v Bob>Hi.</v>\n",
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--style")
+        .arg("heading")
+        .arg("--separator")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Styled conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "### Alice\n\nHello.\n\n***\n\n### Bob\n\nHi.\n\n"
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--style")
+        .arg("table")
+        .arg("--include-timestamps")
+        .arg("first")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "| Time | Speaker | Text |\n| --- | --- | --- |\n\
+         | 00:00:01.000 | Alice | Hello. |\n\
+         | 00:00:03.000 | Bob | Hi. |\n\n"
+    );
+
+    // Layout options only apply to Markdown output
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("html")
+        .arg("--compact")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn test_template_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "minutes.vtt",
+        "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\n<v Alice>Let's begin.</v>\n\n00:00:03.000 --> 00:00:04.000\n<v Bob>Agreed.</v>\n",
+    );
+    let template_path = temp_dir.path().join("minutes.hbs");
+    fs::write(
+        &template_path,
+        "# Minutes: {{title}}\n\nAttendees: {{#each participants}}{{name}}{{#unless @last}}, {{/unless}}{{/each}}\n\n{{#each segments}}- {{start}} {{speaker}}: {{text}}\n{{/each}}",
+    )
+    .unwrap();
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--template")
+        .arg(&template_path)
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Template conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "# Minutes: minutes\n\nAttendees: Alice, Bob\n\n\
+         - 00:00:01.000 Alice: Let's begin.\n\
+         - 00:00:03.000 Bob: Agreed.\n"
+    );
+
+    // Template errors are reported with the line number
+    fs::write(&template_path, "# {{title}}\n\n{{#each segments}}{{speakr}}{{/each}}\n").unwrap();
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--template")
+        .arg(&template_path)
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(65));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("minutes.hbs at line 3"), "stderr: {}", stderr);
+}
+
+#[test]
+fn test_front_matter() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "sync.vtt",
+        "WEBVTT - Weekly sync\n\n00:00:01.000 --> 00:00:02.000\n<v Alice>Hello everyone.</v>\n\n00:01:03.000 --> 00:01:05.500\n<v Bob>Hi.</v>\n",
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--front-matter")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Front matter conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("---\ntitle: \"Weekly sync\"\ndate: "));
+    assert!(stdout.contains(
+        "duration: \"00:01:04\"\nparticipants:\n  - \"Alice\"\n  - \"Bob\"\ncues: 2\nwords: 3\nsource: \"sync.vtt\"\nsource_sha256: \""
+    ));
+    // The Markdown body follows the block
+    assert!(stdout.ends_with("---\n\n**Alice:** Hello everyone.\n\n**Bob:** Hi.\n\n"));
+
+    // Front matter is only written for Markdown output
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--to")
+        .arg("html")
+        .arg("--front-matter")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn test_ics_meeting_details() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "sync.vtt",
+        "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\n<v Alice Smith>Hello everyone.</v>\n\n00:01:03.000 --> 00:01:05.500\n<v Bob>Hi.</v>\n",
+    );
+    let ics_path = temp_dir.path().join("invite.ics");
+    fs::write(
+        &ics_path,
+        "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:Weekly sync\r\nDTSTART;TZID=Europe/Berlin:20251118T150000\r\nORGANIZER;CN=Alice Smith:mailto:alice@example.com\r\nATTENDEE;CN=Alice Smith:mailto:alice@example.com\r\nATTENDEE;CN=Bob:mailto:bob@example.com\r\nATTENDEE;CN=Carol:mailto:carol@example.com\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+    )
+    .unwrap();
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--ics")
+        .arg(&ics_path)
+        .arg("--include-timestamps")
+        .arg("first")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Calendar invite conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with(
+        "# Weekly sync\n\n- **When:** 2025-11-18 15:00:00 (Europe/Berlin)\n- **Organizer:** Alice Smith\n- **Attendees:** Alice Smith, Bob, Carol\n- **Did not speak:** Carol\n\n"
+    ));
+    // Timestamps are wall-clock times from the meeting start
+    assert!(stdout.contains("[15:00:01] **Alice Smith:** Hello everyone."));
+    assert!(stdout.contains("[15:01:03] **Bob:** Hi."));
+
+    // An invite without an event is a data error
+    fs::write(&ics_path, "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n").unwrap();
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--ics")
+        .arg(&ics_path)
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(65));
+}
+
+#[test]
+fn test_timestamp_display_options() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "sync.vtt",
+        "WEBVTT\n\n00:00:05.000 --> 00:00:07.000\n<v Alice>Hello everyone.</v>\n\n00:01:17.500 --> 00:01:19.000\n<v Bob>Hi.</v>\n",
+    );
+    let run = |extra: &[&str]| {
+        let output = Command::new(get_vtt_to_md_path())
+            .arg(&input_path)
+            .args(["--include-timestamps", "first", "--stdout"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute vtt-to-md");
+        (
+            output.status.code(),
+            String::from_utf8_lossy(&output.stdout).into_owned(),
+        )
+    };
+
+    let (code, stdout) = run(&[
+        "--timestamp-precision",
+        "mm:ss",
+        "--timestamp-offset",
+        "-00:00:05",
+    ]);
+    assert_eq!(code, Some(0));
+    assert_eq!(
+        stdout,
+        "[00:00] **Alice:** Hello everyone.\n\n[01:12] **Bob:** Hi.\n\n"
+    );
+
+    let (_, stdout) = run(&["--relative-timestamps"]);
+    assert_eq!(
+        stdout,
+        "[00:00:05.000] **Alice:** Hello everyone.\n\n[+01:12] **Bob:** Hi.\n\n"
+    );
+
+    // Wall-clock times from a UTC start, shown in another zone
+    let (_, stdout) = run(&["--start-time", "14:00Z", "--time-zone", "+01:00"]);
+    assert!(stdout.starts_with("[15:00:05] **Alice:**"));
+
+    // Converting zones needs a start time with a UTC offset
+    let (code, _) = run(&["--start-time", "14:00", "--time-zone", "+01:00"]);
+    assert_eq!(code, Some(64));
+}
+
+#[test]
+fn test_media_url_links() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "sync.vtt",
+        "WEBVTT\n\n00:12:34.500 --> 00:12:36.000\n<v Alice>Hello</v>\n\n00:12:40.000 --> 00:12:42.000\n<v Alice>World</v>\n",
+    );
+    let run = |extra: &[&str]| {
+        Command::new(get_vtt_to_md_path())
+            .arg(&input_path)
+            .args(["--stdout", "--media-url"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute vtt-to-md")
+    };
+
+    // The style is detected from the host; inline cue timestamps are linked too
+    let output = run(&[
+        "https://youtu.be/abc123",
+        "--include-timestamps",
+        "each",
+    ]);
+    assert!(
+        output.status.success(),
+        "Media URL conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "[00:12:34.500](https://youtu.be/abc123?t=754) **Alice:** Hello \
+         [00:12:40.000](https://youtu.be/abc123?t=760) World\n\n"
+    );
+
+    let output = run(&[
+        "https://stream.example/video/123",
+        "--include-timestamps",
+        "first",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("[00:12:34.500](https://stream.example/video/123#t=00:12:34) **Alice:**")
+    );
+
+    // Links are only written in Markdown
+    let output = run(&["https://youtu.be/abc123", "--to", "html"]);
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn test_anchors_and_index() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "sync.vtt",
+        "WEBVTT\n\n00:12:34.500 --> 00:12:36.000\n<v Alice Smith>Hello.</v>\n\n00:12:40.000 --> 00:12:42.000\n<v Bob>Hi.</v>\n",
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--index")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Index conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "## Index\n\n\
+         - [00:12:34.500 Alice Smith](#t-00-12-34-alice-smith)\n\
+         - [00:12:40.000 Bob](#t-00-12-40-bob)\n\n\
+         <a id=\"t-00-12-34-alice-smith\"></a>**Alice Smith:** Hello.\n\n\
+         <a id=\"t-00-12-40-bob\"></a>**Bob:** Hi.\n\n"
+    );
+
+    // Anchors are only written in Markdown
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--anchors")
+        .arg("--to")
+        .arg("qa")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn test_chapters_and_toc() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "sync.vtt",
+        "WEBVTT\n\n00:04:50.000 --> 00:04:55.000\n<v Alice>Let's look at the demo.</v>\n\n00:05:02.000 --> 00:05:06.000\n<v Alice>Here it is.</v>\n\n00:05:10.000 --> 00:05:12.000\n<v Bob>Nice.</v>\n",
+    );
+    let chapters_path = create_test_vtt(
+        &temp_dir,
+        "chapters.vtt",
+        "WEBVTT\n\n00:00:00.000 --> 00:05:00.000\nIntroduction\n\n00:05:00.000 --> 00:10:00.000\nDemo\n",
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--chapters")
+        .arg(&chapters_path)
+        .arg("--toc")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Chapters conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    // Alice's turn is split at the start of the demo chapter
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "## Contents\n\n\
+         - [Introduction](#chapter-introduction)\n\
+         - [Demo](#chapter-demo)\n\n\
+         ## <a id=\"chapter-introduction\"></a>Introduction\n\n\
+         **Alice:** Let's look at the demo.\n\n\
+         ## <a id=\"chapter-demo\"></a>Demo\n\n\
+         **Alice:** Here it is.\n\n\
+         **Bob:** Nice.\n\n"
+    );
+
+    // Structured formats list the chapters in their metadata
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--chapters")
+        .arg(&chapters_path)
+        .arg("--to")
+        .arg("json")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\"chapters\""));
+    assert!(stdout.contains("\"id\": \"chapter-demo\""));
+
+    // A table of contents is only written in Markdown
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--chapters")
+        .arg(&chapters_path)
+        .arg("--toc")
+        .arg("--to")
+        .arg("qa")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn test_topic_sections() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut content = String::from("WEBVTT\n");
+    for (minute, text) in [
+        (0, "Budget forecast: revenue growth and higher spending this quarter."),
+        (1, "Hiring engineers: candidates, interviews and offers for the team this quarter."),
+    ] {
+        for second in 0..24 {
+            content.push_str(&format!(
+                "\n00:{:02}:{:02}.000 --> 00:{:02}:{:02}.500\n<v {}>{}</v>\n",
+                minute,
+                second * 2,
+                minute,
+                second * 2 + 1,
+                if second % 2 == 0 { "Alice" } else { "Bob" },
+                text
+            ));
+        }
+    }
+    let input_path = create_test_vtt(&temp_dir, "planning.vtt", &content);
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--topics")
+        .arg("--toc")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Topics conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with(
+        "## Contents\n\n\
+         - [Budget, forecast, revenue](#section-budget-forecast-revenue)\n\
+         - [Hiring, engineers, candidates](#section-hiring-engineers-candidates)\n\n\
+         ## <a id=\"section-budget-forecast-revenue\"></a>Budget, forecast, revenue\n\n\
+         **Alice:** Budget forecast"
+    ));
+    assert!(stdout.contains(
+        "## <a id=\"section-hiring-engineers-candidates\"></a>Hiring, engineers, candidates\n\n\
+         **Alice:** Hiring engineers"
+    ));
+
+    // A table of contents needs chapters or sections
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--toc")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn test_max_gap_and_pause_markers() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "standup.vtt",
+        "WEBVTT\n\n00:00:01.000 --> 00:00:03.000\n<v Alice>Let's take a break.</v>\n\n00:03:15.000 --> 00:03:17.000\n<v Alice>We're back.</v>\n",
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--max-gap")
+        .arg("2m")
+        .arg("--pause-markers")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Max gap conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "**Alice:** Let's take a break.\n\n[pause 3m12s]\n\n**Alice:** We're back.\n\n"
+    );
+
+    // Pause markers need a gap threshold
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--pause-markers")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--max-gap")
+        .arg("soon")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_paragraph_splitting() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = create_test_vtt(
+        &temp_dir,
+        "keynote.vtt",
+        "WEBVTT\n\n00:00:01.000 --> 00:00:03.000\n<v Alice>Welcome, everyone.</v>\n\n00:00:03.000 --> 00:00:06.000\n<v Alice>Today is about our roadmap.</v>\n\n00:00:06.000 --> 00:00:09.000\n<v Alice>First, the launch.</v>\n\n00:00:30.000 --> 00:00:33.000\n<v Alice>Next, hiring.</v>\n",
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--paragraph-sentences")
+        .arg("2")
+        .arg("--paragraph-pause")
+        .arg("10s")
+        .arg("--include-timestamps")
+        .arg("first")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert!(
+        output.status.success(),
+        "Paragraph conversion failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "[00:00:01.000] **Alice:** Welcome, everyone. Today is about our roadmap.\n\n\
+         [00:00:06.000] First, the launch.\n\n\
+         [00:00:30.000] Next, hiring.\n\n"
+    );
+
+    let output = Command::new(get_vtt_to_md_path())
+        .arg(&input_path)
+        .arg("--paragraph-words")
+        .arg("0")
+        .arg("--stdout")
+        .output()
+        .expect("Failed to execute vtt-to-md");
+    assert_eq!(output.status.code(), Some(2));
+}